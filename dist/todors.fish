@@ -1,30 +1,31 @@
 # Print an optspec for argparse to handle cmd's options that are independent of any subcommand.
 function __fish_todors_global_optspecs
-	string join \n c/config= h/help V/version
+    string join \n c/config= set= h/help V/version
 end
 
 function __fish_todors_needs_command
-	# Figure out if the current invocation already has a command.
-	set -l cmd (commandline -opc)
-	set -e cmd[1]
-	argparse -s (__fish_todors_global_optspecs) -- $cmd 2>/dev/null
-	or return
-	if set -q argv[1]
-		# Also print the command, so this can be used to figure out what it is.
-		echo $argv[1]
-		return 1
-	end
-	return 0
+    # Figure out if the current invocation already has a command.
+    set -l cmd (commandline -opc)
+    set -e cmd[1]
+    argparse -s (__fish_todors_global_optspecs) -- $cmd 2>/dev/null
+    or return
+    if set -q argv[1]
+        # Also print the command, so this can be used to figure out what it is.
+        echo $argv[1]
+        return 1
+    end
+    return 0
 end
 
 function __fish_todors_using_subcommand
-	set -l cmd (__fish_todors_needs_command)
-	test -z "$cmd"
-	and return 1
-	contains -- $cmd[1] $argv
+    set -l cmd (__fish_todors_needs_command)
+    test -z "$cmd"
+    and return 1
+    contains -- $cmd[1] $argv
 end
 
 complete -c todors -n "__fish_todors_needs_command" -s c -l config -d 'Path to the config file.' -r -F
+complete -c todors -n "__fish_todors_needs_command" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_needs_command" -s h -l help -d 'Print help'
 complete -c todors -n "__fish_todors_needs_command" -s V -l version -d 'Print version'
 complete -c todors -n "__fish_todors_needs_command" -f -a "add" -d 'Add a task to the list'
@@ -35,53 +36,203 @@ complete -c todors -n "__fish_todors_needs_command" -f -a "list" -d 'List all th
 complete -c todors -n "__fish_todors_needs_command" -f -a "ls" -d 'List all the tasks or those that match the query'
 complete -c todors -n "__fish_todors_needs_command" -f -a "remove" -d 'Remove selected item from the todo file'
 complete -c todors -n "__fish_todors_needs_command" -f -a "rm" -d 'Remove selected item from the todo file'
-complete -c todors -n "__fish_todors_needs_command" -f -a "edit" -d 'Edit the todo file with a text editor'
-complete -c todors -n "__fish_todors_needs_command" -f -a "e" -d 'Edit the todo file with a text editor'
+complete -c todors -n "__fish_todors_needs_command" -f -a "edit" -d 'Edit the todo file, or only the selected tasks, with a text editor'
+complete -c todors -n "__fish_todors_needs_command" -f -a "e" -d 'Edit the todo file, or only the selected tasks, with a text editor'
 complete -c todors -n "__fish_todors_needs_command" -f -a "due" -d 'List all due tasks'
+complete -c todors -n "__fish_todors_needs_command" -f -a "cal" -d 'Show the tasks due each day of a month, or the coming ones'
 complete -c todors -n "__fish_todors_needs_command" -f -a "undone" -d 'Mark selected tasks as not done'
 complete -c todors -n "__fish_todors_needs_command" -f -a "undo" -d 'Mark selected tasks as not done'
 complete -c todors -n "__fish_todors_needs_command" -f -a "clean" -d 'Clean all the completed tasks'
 complete -c todors -n "__fish_todors_needs_command" -f -a "modify" -d 'Modify selected tasks as desired'
 complete -c todors -n "__fish_todors_needs_command" -f -a "mod" -d 'Modify selected tasks as desired'
 complete -c todors -n "__fish_todors_needs_command" -f -a "next" -d 'Show the next task to do base on the urgency task sort we have'
+complete -c todors -n "__fish_todors_needs_command" -f -a "tui" -d 'Browse and edit tasks in an interactive terminal interface'
+complete -c todors -n "__fish_todors_needs_command" -f -a "import" -d 'Append the tasks of another list to this one'
+complete -c todors -n "__fish_todors_needs_command" -f -a "export" -d 'Export tasks, to stdout or a file'
+complete -c todors -n "__fish_todors_needs_command" -f -a "sync" -d 'Commit the todo files, pull the remote changes and push them back with git'
+complete -c todors -n "__fish_todors_needs_command" -f -a "caldav" -d 'Sync the tasks with the CalDAV collection set as `caldav.url` in the config'
+complete -c todors -n "__fish_todors_needs_command" -f -a "merge" -d 'Three-way merge of todo.txt files, keeping the changes made on each side'
+complete -c todors -n "__fish_todors_needs_command" -f -a "serve" -d 'Serve a JSON API to read and update the tasks over HTTP'
+complete -c todors -n "__fish_todors_needs_command" -f -a "start" -d 'Start a timer on a task, the elapsed time is added to its `spent:` tag when stopped'
+complete -c todors -n "__fish_todors_needs_command" -f -a "stop" -d 'Stop the running timer'
+complete -c todors -n "__fish_todors_needs_command" -f -a "report" -d 'Summaries of the tasks, including the done archive'
+complete -c todors -n "__fish_todors_needs_command" -f -a "stats" -d 'Created and completed tasks over time, lead time and overdue tasks, including the done archive'
+complete -c todors -n "__fish_todors_needs_command" -f -a "remind" -d 'Notify of the tasks due today, overdue or whose `remind:` time has come, once each'
+complete -c todors -n "__fish_todors_needs_command" -f -a "config" -d 'Bootstrap and inspect the configuration'
 complete -c todors -n "__fish_todors_needs_command" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c todors -n "__fish_todors_using_subcommand add" -l pri -d 'Set the priority directly after creating the task' -r
+complete -c todors -n "__fish_todors_using_subcommand add" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand add" -s h -l help -d 'Print help'
 complete -c todors -n "__fish_todors_using_subcommand a" -l pri -d 'Set the priority directly after creating the task' -r
+complete -c todors -n "__fish_todors_using_subcommand a" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand a" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand done" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand done" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand do" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand do" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand list" -l by -d 'Group the tasks by their first project or context' -r -f -a "project\t''
+context\t''"
+complete -c todors -n "__fish_todors_using_subcommand list" -l capacity -d 'Only the most urgent tasks whose remaining estimate fits in this time, e.g. `6h`' -r
+complete -c todors -n "__fish_todors_using_subcommand list" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand list" -l all -d 'Display all tasks, even the completed ones'
+complete -c todors -n "__fish_todors_using_subcommand list" -l watch -d 'Keep running and redraw whenever the todo file changes'
 complete -c todors -n "__fish_todors_using_subcommand list" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand ls" -l by -d 'Group the tasks by their first project or context' -r -f -a "project\t''
+context\t''"
+complete -c todors -n "__fish_todors_using_subcommand ls" -l capacity -d 'Only the most urgent tasks whose remaining estimate fits in this time, e.g. `6h`' -r
+complete -c todors -n "__fish_todors_using_subcommand ls" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand ls" -l all -d 'Display all tasks, even the completed ones'
+complete -c todors -n "__fish_todors_using_subcommand ls" -l watch -d 'Keep running and redraw whenever the todo file changes'
 complete -c todors -n "__fish_todors_using_subcommand ls" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand remove" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand remove" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand rm" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand rm" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand edit" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand edit" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand e" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand e" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand due" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand due" -l watch -d 'Keep running and redraw whenever the todo file changes'
 complete -c todors -n "__fish_todors_using_subcommand due" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand cal" -l agenda -d 'List the tasks due in the next days, 7 by default, under their date' -r
+complete -c todors -n "__fish_todors_using_subcommand cal" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand cal" -l completed -d 'Also count the tasks completed each day'
+complete -c todors -n "__fish_todors_using_subcommand cal" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand undone" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand undone" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand undo" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand undo" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand clean" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand clean" -s h -l help -d 'Print help'
 complete -c todors -n "__fish_todors_using_subcommand modify" -l priority -l pri -r
 complete -c todors -n "__fish_todors_using_subcommand modify" -l due-date -r
+complete -c todors -n "__fish_todors_using_subcommand modify" -l parent -d 'Make the tasks subtasks of this one' -r
+complete -c todors -n "__fish_todors_using_subcommand modify" -l dep -d 'Mark the tasks as blocked by these ones, e.g. `--dep 3,5`' -r
+complete -c todors -n "__fish_todors_using_subcommand modify" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand modify" -l rm-priority -l rm-pri
 complete -c todors -n "__fish_todors_using_subcommand modify" -l rm-due-date
+complete -c todors -n "__fish_todors_using_subcommand modify" -l rm-parent
+complete -c todors -n "__fish_todors_using_subcommand modify" -l rm-dep
 complete -c todors -n "__fish_todors_using_subcommand modify" -s h -l help -d 'Print help'
 complete -c todors -n "__fish_todors_using_subcommand mod" -l priority -l pri -r
 complete -c todors -n "__fish_todors_using_subcommand mod" -l due-date -r
+complete -c todors -n "__fish_todors_using_subcommand mod" -l parent -d 'Make the tasks subtasks of this one' -r
+complete -c todors -n "__fish_todors_using_subcommand mod" -l dep -d 'Mark the tasks as blocked by these ones, e.g. `--dep 3,5`' -r
+complete -c todors -n "__fish_todors_using_subcommand mod" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
 complete -c todors -n "__fish_todors_using_subcommand mod" -l rm-priority -l rm-pri
 complete -c todors -n "__fish_todors_using_subcommand mod" -l rm-due-date
+complete -c todors -n "__fish_todors_using_subcommand mod" -l rm-parent
+complete -c todors -n "__fish_todors_using_subcommand mod" -l rm-dep
 complete -c todors -n "__fish_todors_using_subcommand mod" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand next" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand next" -l watch -d 'Keep running and redraw whenever the todo file changes'
 complete -c todors -n "__fish_todors_using_subcommand next" -s h -l help -d 'Print help'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "add" -d 'Add a task to the list'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "done" -d 'Mark selected tasks as done'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "list" -d 'List all the tasks or those that match the query'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "remove" -d 'Remove selected item from the todo file'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "edit" -d 'Edit the todo file with a text editor'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "due" -d 'List all due tasks'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "undone" -d 'Mark selected tasks as not done'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "clean" -d 'Clean all the completed tasks'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "modify" -d 'Modify selected tasks as desired'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "next" -d 'Show the next task to do base on the urgency task sort we have'
-complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due undone clean modify next help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand tui" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand tui" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand import" -l from -r -f -a "todo-txt\t''
+sqlite\t''
+taskwarrior-json\t'The output of `task export`'
+todoist-csv\t'A Todoist project exported as CSV'
+markdown\t'`- [ ]` and `- [x]` checklist items'"
+complete -c todors -n "__fish_todors_using_subcommand import" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand import" -l dry-run -d 'Print the imported tasks as todo.txt lines without saving them'
+complete -c todors -n "__fish_todors_using_subcommand import" -l headings-as-projects -d 'Use the Markdown heading above each item as its project, e.g. `## Release 2.0` as +Release-2.0'
+complete -c todors -n "__fish_todors_using_subcommand import" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c todors -n "__fish_todors_using_subcommand export" -l to -r -f -a "todo-txt\t''
+sqlite\t''
+ics\t'iCalendar VTODO entries'
+markdown\t'`- [ ]` and `- [x]` checklist'
+html\t'HTML checklist fragment'"
+complete -c todors -n "__fish_todors_using_subcommand export" -s o -l output -d 'Write to this file instead of stdout' -r -F
+complete -c todors -n "__fish_todors_using_subcommand export" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand export" -l by-project -d 'Group the Markdown and HTML checklists by project'
+complete -c todors -n "__fish_todors_using_subcommand export" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c todors -n "__fish_todors_using_subcommand sync" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand sync" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand caldav" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand caldav" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand merge" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand merge" -s p -l stdout -d 'Print the merged file instead of replacing <OURS>'
+complete -c todors -n "__fish_todors_using_subcommand merge" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand serve" -l bind -d 'Address to listen on, defaults to the `server.bind` config key' -r
+complete -c todors -n "__fish_todors_using_subcommand serve" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand serve" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand start" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand start" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand stop" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand stop" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand report; and not __fish_seen_subcommand_from time burndown help" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand report; and not __fish_seen_subcommand_from time burndown help" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand report; and not __fish_seen_subcommand_from time burndown help" -f -a "time" -d 'Time spent on the tasks, from their `spent:` tag'
+complete -c todors -n "__fish_todors_using_subcommand report; and not __fish_seen_subcommand_from time burndown help" -f -a "burndown" -d 'Open tasks at the end of each day'
+complete -c todors -n "__fish_todors_using_subcommand report; and not __fish_seen_subcommand_from time burndown help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from time" -l by -d 'Sum the time per project or context instead of per task' -r -f -a "project\t''
+context\t''"
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from time" -l since -d 'Only count the tasks completed since this date, open tasks are always counted' -r
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from time" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from time" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from burndown" -l days -d 'Number of days to show, up to today' -r
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from burndown" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from burndown" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from help" -f -a "time" -d 'Time spent on the tasks, from their `spent:` tag'
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from help" -f -a "burndown" -d 'Open tasks at the end of each day'
+complete -c todors -n "__fish_todors_using_subcommand report; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand stats" -l days -d 'Number of days of daily activity, up to today' -r
+complete -c todors -n "__fish_todors_using_subcommand stats" -l weeks -d 'Number of weeks of weekly activity' -r
+complete -c todors -n "__fish_todors_using_subcommand stats" -l by -d 'Break the tasks down per project or context' -r -f -a "project\t''
+context\t''"
+complete -c todors -n "__fish_todors_using_subcommand stats" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand stats" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand remind" -l interval -d 'Time between two checks, defaults to the `remind.interval` config key' -r
+complete -c todors -n "__fish_todors_using_subcommand remind" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand remind" -l watch -d 'Keep running and check again periodically'
+complete -c todors -n "__fish_todors_using_subcommand remind" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -f -a "init" -d 'Create the config file, the todo directory and an empty todo.txt file'
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -f -a "show" -d 'Print the effective configuration'
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -f -a "path" -d 'Print the path of the config file'
+complete -c todors -n "__fish_todors_using_subcommand config; and not __fish_seen_subcommand_from init show path help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from init" -l todo-dir -d 'Directory where todo.txt is stored' -r -F
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from init" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from init" -l force -d 'Overwrite the config file if it already exists'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from init" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from show" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from show" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from path" -l set -d 'Override a config key, takes precedence over the config file and env vars.' -r
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from path" -s h -l help -d 'Print help'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "init" -d 'Create the config file, the todo directory and an empty todo.txt file'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "show" -d 'Print the effective configuration'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "path" -d 'Print the path of the config file'
+complete -c todors -n "__fish_todors_using_subcommand config; and __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "add" -d 'Add a task to the list'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "done" -d 'Mark selected tasks as done'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "list" -d 'List all the tasks or those that match the query'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "remove" -d 'Remove selected item from the todo file'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "edit" -d 'Edit the todo file, or only the selected tasks, with a text editor'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "due" -d 'List all due tasks'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "cal" -d 'Show the tasks due each day of a month, or the coming ones'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "undone" -d 'Mark selected tasks as not done'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "clean" -d 'Clean all the completed tasks'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "modify" -d 'Modify selected tasks as desired'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "next" -d 'Show the next task to do base on the urgency task sort we have'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "tui" -d 'Browse and edit tasks in an interactive terminal interface'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "import" -d 'Append the tasks of another list to this one'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "export" -d 'Export tasks, to stdout or a file'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "sync" -d 'Commit the todo files, pull the remote changes and push them back with git'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "caldav" -d 'Sync the tasks with the CalDAV collection set as `caldav.url` in the config'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "merge" -d 'Three-way merge of todo.txt files, keeping the changes made on each side'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "serve" -d 'Serve a JSON API to read and update the tasks over HTTP'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "start" -d 'Start a timer on a task, the elapsed time is added to its `spent:` tag when stopped'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "stop" -d 'Stop the running timer'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "report" -d 'Summaries of the tasks, including the done archive'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "stats" -d 'Created and completed tasks over time, lead time and overdue tasks, including the done archive'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "remind" -d 'Notify of the tasks due today, overdue or whose `remind:` time has come, once each'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "config" -d 'Bootstrap and inspect the configuration'
+complete -c todors -n "__fish_todors_using_subcommand help; and not __fish_seen_subcommand_from add done list remove edit due cal undone clean modify next tui import export sync caldav merge serve start stop report stats remind config help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c todors -n "__fish_todors_using_subcommand help; and __fish_seen_subcommand_from report" -f -a "time" -d 'Time spent on the tasks, from their `spent:` tag'
+complete -c todors -n "__fish_todors_using_subcommand help; and __fish_seen_subcommand_from report" -f -a "burndown" -d 'Open tasks at the end of each day'
+complete -c todors -n "__fish_todors_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "init" -d 'Create the config file, the todo directory and an empty todo.txt file'
+complete -c todors -n "__fish_todors_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "show" -d 'Print the effective configuration'
+complete -c todors -n "__fish_todors_using_subcommand help; and __fish_seen_subcommand_from config" -f -a "path" -d 'Print the path of the config file'
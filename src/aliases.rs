@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{tasks::error::TaskError, utils::split_command};
+
+const VIEW_PREFIX: &str = "@view:";
+
+/// Replace the alias name, the first of the `external` args clap couldn't match to a command, by
+/// its definition. The global args before it and the extra args after it are kept as is.
+pub fn expand_alias(args: &[String], external: &[String], alias: &str) -> Vec<String> {
+    let (prefix, _) = args.split_at(args.len() - external.len());
+
+    prefix
+        .iter()
+        .cloned()
+        .chain(split_command(alias))
+        .chain(external.iter().skip(1).cloned())
+        .collect()
+}
+
+/// Replace every `@view:<name>` arg by the query saved under that name, split like an alias. The
+/// view's terms join the other query terms, and a query matches the tasks matching any of its
+/// terms: `@view:work #next` lists the work tasks and the `#next` ones, it doesn't narrow the view.
+pub fn expand_views(
+    args: &[String],
+    views: &BTreeMap<String, String>,
+) -> Result<Vec<String>, TaskError> {
+    let mut expanded = Vec::new();
+
+    for arg in args {
+        expand_view(arg, views, &mut HashSet::new(), &mut expanded)?;
+    }
+
+    Ok(expanded)
+}
+
+fn expand_view<'a>(
+    arg: &str,
+    views: &'a BTreeMap<String, String>,
+    seen: &mut HashSet<&'a str>,
+    expanded: &mut Vec<String>,
+) -> Result<(), TaskError> {
+    let Some(name) = arg.strip_prefix(VIEW_PREFIX) else {
+        expanded.push(arg.to_string());
+        return Ok(());
+    };
+
+    let Some((name, view)) = views.get_key_value(name) else {
+        return Err(TaskError::UnknownView(name.to_string()));
+    };
+
+    // A view can use other views, but not itself
+    if !seen.insert(name.as_str()) {
+        return Err(TaskError::RecursiveView(name.clone()));
+    }
+    for word in split_command(view) {
+        expand_view(&word, views, seen, expanded)?;
+    }
+    seen.remove(name.as_str());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_alias, expand_views};
+    use crate::tasks::error::TaskError;
+    use std::collections::BTreeMap;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn it_expands_alias_and_keeps_surrounding_args() {
+        let result = expand_alias(
+            &args("todors -c config.toml today +work"),
+            &args("today +work"),
+            "list --all #today",
+        );
+
+        assert_eq!(
+            result,
+            args("todors -c config.toml list --all #today +work")
+        );
+    }
+
+    #[test]
+    fn it_expands_views_with_extra_terms() {
+        let views = BTreeMap::from([
+            ("work".to_string(), "+work @office".to_string()),
+            ("all".to_string(), "@view:work +home".to_string()),
+        ]);
+
+        let result = expand_views(&args("todors ls @view:all #next"), &views).unwrap();
+
+        assert_eq!(result, args("todors ls +work @office +home #next"));
+    }
+
+    #[test]
+    fn it_keeps_quoted_words_of_a_view_together() {
+        let views = BTreeMap::from([("calls".to_string(), "'call mom' @phone".to_string())]);
+
+        let result = expand_views(&args("ls @view:calls"), &views).unwrap();
+
+        assert_eq!(result, ["ls", "call mom", "@phone"]);
+    }
+
+    #[test]
+    fn it_fails_on_unknown_or_recursive_views() {
+        let views = BTreeMap::from([("loop".to_string(), "@view:loop".to_string())]);
+
+        assert!(matches!(
+            expand_views(&args("ls @view:nope"), &views),
+            Err(TaskError::UnknownView(_))
+        ));
+        assert!(matches!(
+            expand_views(&args("ls @view:loop"), &views),
+            Err(TaskError::RecursiveView(_))
+        ));
+    }
+}
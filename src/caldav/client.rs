@@ -0,0 +1,321 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ureq::{
+    Agent,
+    http::{Method, Request},
+};
+
+use crate::{config::CalDavConfig, tasks::error::TaskError};
+
+const PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+
+/// A calendar object of the collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    pub href: String,
+    pub etag: String,
+}
+
+/// The ETag expected on the server for a write to succeed.
+pub enum Precondition<'a> {
+    /// The resource must not exist yet
+    Create,
+    /// The resource must not have changed since it was read
+    Match(&'a str),
+}
+
+/// The outcome of a conditional write.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Written {
+    /// The new ETag when the server returned it
+    Done(Option<String>),
+    /// The resource changed on the server in the meantime
+    Conflict,
+}
+
+/// The few WebDAV requests needed to sync a CalDAV collection.
+pub struct CalDavClient {
+    agent: Agent,
+    collection: String,
+    authorization: Option<String>,
+}
+
+struct Reply {
+    status: u16,
+    etag: Option<String>,
+    body: String,
+}
+
+impl CalDavClient {
+    pub fn new(config: &CalDavConfig) -> Result<Self, TaskError> {
+        let Some(url) = &config.url else {
+            return Err(TaskError::CalDavNotConfigured);
+        };
+
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .allow_non_standard_methods(true)
+            .build()
+            .into();
+        let authorization = config.username.as_ref().map(|username| {
+            let password = config.password.as_deref().unwrap_or("");
+            format!(
+                "Basic {}",
+                STANDARD.encode(format!("{username}:{password}"))
+            )
+        });
+
+        Ok(Self {
+            agent,
+            collection: format!("{}/", url.trim_end_matches('/')),
+            authorization,
+        })
+    }
+
+    /// The href of a new resource for the task `uid`.
+    pub fn href_for(&self, uid: &str) -> String {
+        format!("{}{uid}.ics", self.normalize(&self.collection))
+    }
+
+    /// The path of `url`, hrefs returned by the server are paths.
+    fn path<'a>(&self, url: &'a str) -> &'a str {
+        let after_scheme = url.find("://").map_or(0, |idx| idx + 3);
+        url[after_scheme..]
+            .find('/')
+            .map_or("/", |idx| &url[after_scheme + idx..])
+    }
+
+    /// Hrefs may be absolute URLs and are percent-encoded, they're compared by their decoded
+    /// path.
+    fn normalize(&self, href: &str) -> String {
+        let path = if href.contains("://") {
+            self.path(href)
+        } else {
+            href
+        };
+
+        percent_decode(path)
+    }
+
+    fn url(&self, href: &str) -> String {
+        let path = self.path(&self.collection);
+        let origin = &self.collection[..self.collection.len() - path.len()];
+
+        format!("{origin}{}", percent_encode(href))
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<Reply, TaskError> {
+        let mut request = Request::builder()
+            .method(Method::from_bytes(method.as_bytes()).expect("The method is valid"))
+            .uri(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+
+        let reply = request
+            .body(body)
+            .map_err(|err| err.to_string())
+            .and_then(|request| self.agent.run(request).map_err(|err| err.to_string()))
+            .and_then(|mut response| {
+                let etag = response
+                    .headers()
+                    .get("ETag")
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let body = response
+                    .body_mut()
+                    .read_to_string()
+                    .map_err(|err| err.to_string())?;
+
+                Ok(Reply {
+                    status: response.status().as_u16(),
+                    etag,
+                    body,
+                })
+            });
+
+        reply.map_err(|err| {
+            eprintln!("{method} {url} failed: {err}");
+            TaskError::FailedToReachServer
+        })
+    }
+
+    fn unexpected(method: &str, href: &str, reply: &Reply) -> TaskError {
+        eprintln!("Unexpected {} for {method} {href}", reply.status);
+        TaskError::InvalidServerResponse
+    }
+
+    /// All the calendar objects of the collection with their ETag.
+    pub fn list(&self) -> Result<Vec<Resource>, TaskError> {
+        let reply = self.send(
+            "PROPFIND",
+            &self.collection,
+            &[("Depth", "1"), ("Content-Type", "application/xml")],
+            PROPFIND.to_string(),
+        )?;
+        if reply.status != 207 {
+            return Err(Self::unexpected("PROPFIND", &self.collection, &reply));
+        }
+
+        let document = roxmltree::Document::parse(&reply.body).map_err(|err| {
+            eprintln!("Invalid PROPFIND response: {err}");
+            TaskError::InvalidServerResponse
+        })?;
+
+        let text = |node: roxmltree::Node, name: &str| {
+            node.descendants()
+                .find(|child| child.has_tag_name(("DAV:", name)))
+                .and_then(|child| child.text())
+                .map(|text| text.trim().to_string())
+        };
+
+        // The collection itself is listed too, without an ETag
+        Ok(document
+            .descendants()
+            .filter(|node| node.has_tag_name(("DAV:", "response")))
+            .filter_map(|response| {
+                Some(Resource {
+                    href: self.normalize(&text(response, "href")?),
+                    etag: text(response, "getetag")?,
+                })
+            })
+            .filter(|resource| {
+                resource.href.trim_end_matches('/')
+                    != self.normalize(&self.collection).trim_end_matches('/')
+            })
+            .collect())
+    }
+
+    /// The content of a calendar object and its ETag.
+    pub fn get(&self, href: &str) -> Result<(String, Option<String>), TaskError> {
+        let reply = self.send("GET", &self.url(href), &[], String::new())?;
+        if reply.status != 200 {
+            return Err(Self::unexpected("GET", href, &reply));
+        }
+
+        Ok((reply.body, reply.etag))
+    }
+
+    pub fn put(
+        &self,
+        href: &str,
+        ics: String,
+        precondition: Precondition,
+    ) -> Result<Written, TaskError> {
+        let condition = match precondition {
+            Precondition::Create => ("If-None-Match", "*"),
+            Precondition::Match(etag) => ("If-Match", etag),
+        };
+        let headers = [("Content-Type", "text/calendar; charset=utf-8"), condition];
+
+        let reply = self.send("PUT", &self.url(href), &headers, ics)?;
+        match reply.status {
+            200..=299 => Ok(Written::Done(reply.etag)),
+            412 => Ok(Written::Conflict),
+            _ => Err(Self::unexpected("PUT", href, &reply)),
+        }
+    }
+
+    pub fn delete(&self, href: &str, etag: &str) -> Result<Written, TaskError> {
+        let reply = self.send(
+            "DELETE",
+            &self.url(href),
+            &[("If-Match", etag)],
+            String::new(),
+        )?;
+        match reply.status {
+            200..=299 | 404 => Ok(Written::Done(None)),
+            412 => Ok(Written::Conflict),
+            _ => Err(Self::unexpected("DELETE", href, &reply)),
+        }
+    }
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encode what can't be in the path of a URL, like spaces.
+fn percent_encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b'@'
+            | b':'
+            | b'+'
+            | b'='
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b','
+            | b';' => (byte as char).to_string(),
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CalDavClient;
+    use crate::config::CalDavConfig;
+
+    #[test]
+    fn it_compares_hrefs_by_their_decoded_path() {
+        let client = CalDavClient::new(&CalDavConfig {
+            url: Some("https://dav.example.com/calendars/me/my%20tasks".to_string()),
+            ..CalDavConfig::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            client.normalize("https://dav.example.com/calendars/me/my%20tasks/1.ics"),
+            "/calendars/me/my tasks/1.ics"
+        );
+        assert_eq!(client.href_for("1"), "/calendars/me/my tasks/1.ics");
+        assert_eq!(
+            client.url("/calendars/me/my tasks/1.ics"),
+            "https://dav.example.com/calendars/me/my%20tasks/1.ics"
+        );
+    }
+}
@@ -0,0 +1,594 @@
+mod client;
+mod vtodo;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    hooks::HookEvent,
+    merge::{merge_task, same},
+    storage::TaskStorage,
+    tasks::{
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        task::Task,
+    },
+    utils::{load_json, save_json},
+};
+
+pub use client::{CalDavClient, Precondition, Resource, Written};
+pub use vtodo::{from_calendar, to_calendar};
+
+/// A task as it was on both sides after the last sync, the base of the next one.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Synced {
+    href: String,
+    etag: String,
+    line: String,
+}
+
+/// The synced tasks by `id:` tag.
+type SyncState = BTreeMap<String, Synced>;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    pub conflicts: usize,
+}
+
+fn uid(task: &Task) -> String {
+    task.stable_id().unwrap_or_default().to_string()
+}
+
+/// Sync the tasks with the CalDAV collection of `client`. Each task is a calendar object named
+/// after its `id:` tag, which is added to the tasks without one when they're first pushed.
+///
+/// Tasks changed on a single side since the last sync take that side, tasks changed on both are
+/// merged field by field like `todors merge` does. A task edited on one side wins over its
+/// deletion on the other. Tasks that can't be merged, or changed on the server while being
+/// written, are left untouched on both sides and counted as conflicts: they'll be retried on the
+/// next sync.
+pub fn sync(
+    storage: &TaskStorage,
+    client: &CalDavClient,
+    state_file: &Path,
+) -> Result<SyncReport, TaskError> {
+    let _lock = storage.lock()?;
+
+    let mut tasks = storage.get_all()?;
+
+    let mut state: SyncState = load_json(state_file)?.unwrap_or_default();
+    let mut report = SyncReport::default();
+    let now = Utc::now();
+
+    let remote: HashMap<String, String> = client
+        .list()?
+        .into_iter()
+        .map(|resource| (resource.href, resource.etag))
+        .collect();
+
+    let mut conflicting = HashSet::new();
+
+    // Tasks created on the server since the last sync
+    let known: Vec<&String> = state.values().map(|synced| &synced.href).collect();
+    let created: Vec<(&String, &String)> = remote
+        .iter()
+        .filter(|(href, _)| !known.contains(href))
+        .collect();
+    for (href, etag) in created {
+        let (ics, _) = client.get(href)?;
+        let theirs = from_calendar(&ics)?;
+        let uid = uid(&theirs);
+
+        match tasks.iter().position(|task| task.stable_id() == Some(&uid)) {
+            None => {
+                tasks.push(theirs.clone());
+                report.pulled += 1;
+            }
+            // Without a base to merge, a different task with the same id is a conflict
+            Some(idx) if !same(&tasks[idx], &theirs) => {
+                conflicting.insert(uid);
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        state.insert(
+            uid,
+            Synced {
+                href: href.clone(),
+                etag: etag.clone(),
+                line: theirs.to_string(),
+            },
+        );
+    }
+
+    let synced: Vec<(String, Synced)> = state
+        .iter()
+        .map(|(uid, synced)| (uid.clone(), synced.clone()))
+        .collect();
+    for (uid, synced) in synced {
+        let base = Task::from_str(0, &synced.line)?;
+        let local = tasks.iter().position(|task| task.stable_id() == Some(&uid));
+
+        let Some(etag) = remote.get(&synced.href) else {
+            // Deleted on the server
+            match local {
+                Some(idx) if !same(&tasks[idx], &base) => {
+                    let ics = to_calendar(&tasks[idx], now);
+                    match client.put(&synced.href, ics, Precondition::Create)? {
+                        Written::Done(etag) => {
+                            state.insert(uid, synced_as(&synced.href, etag, &tasks[idx]));
+                            report.pushed += 1;
+                        }
+                        Written::Conflict => report.conflicts += 1,
+                    }
+                }
+                Some(idx) => {
+                    tasks.remove(idx);
+                    state.remove(&uid);
+                    report.deleted += 1;
+                }
+                None => {
+                    state.remove(&uid);
+                }
+            }
+            continue;
+        };
+
+        let theirs = if *etag == synced.etag {
+            base.clone()
+        } else {
+            from_calendar(&client.get(&synced.href)?.0)?
+        };
+        let theirs_changed = !same(&theirs, &base);
+
+        let Some(idx) = local else {
+            // Deleted locally
+            if theirs_changed {
+                tasks.push(theirs.clone());
+                state.insert(uid, synced_as(&synced.href, Some(etag.clone()), &theirs));
+                report.pulled += 1;
+            } else if client.delete(&synced.href, etag)? == Written::Conflict {
+                report.conflicts += 1;
+            } else {
+                state.remove(&uid);
+                report.deleted += 1;
+            }
+            continue;
+        };
+
+        let ours_changed = !same(&tasks[idx], &base);
+        let task = match (ours_changed, theirs_changed) {
+            (false, false) => {
+                state.insert(uid, synced_as(&synced.href, Some(etag.clone()), &base));
+                continue;
+            }
+            (false, true) => {
+                tasks[idx] = Task {
+                    id: tasks[idx].id,
+                    ..theirs
+                };
+                state.insert(
+                    uid,
+                    synced_as(&synced.href, Some(etag.clone()), &tasks[idx]),
+                );
+                report.pulled += 1;
+                continue;
+            }
+            (true, false) => tasks[idx].clone(),
+            (true, true) => match merge_task(&base, &tasks[idx], &theirs) {
+                Some(merged) => merged,
+                None => {
+                    report.conflicts += 1;
+                    continue;
+                }
+            },
+        };
+
+        let ics = to_calendar(&task, now);
+        match client.put(&synced.href, ics, Precondition::Match(etag))? {
+            Written::Done(etag) => {
+                state.insert(uid, synced_as(&synced.href, etag, &task));
+                tasks[idx] = Task {
+                    id: tasks[idx].id,
+                    ..task
+                };
+                report.pushed += 1;
+            }
+            Written::Conflict => report.conflicts += 1,
+        }
+    }
+
+    // Tasks created locally since the last sync, they're given an `id:` tag when pushed
+    report.conflicts += conflicting.len();
+    let created: Vec<usize> = (0..tasks.len())
+        .filter(|&idx| {
+            tasks[idx]
+                .stable_id()
+                .is_none_or(|id| !state.contains_key(id) && !conflicting.contains(id))
+        })
+        .collect();
+    for idx in created {
+        let uid = tasks.stable_id_at(idx);
+        let href = client.href_for(&uid);
+        match client.put(&href, to_calendar(&tasks[idx], now), Precondition::Create)? {
+            Written::Done(etag) => {
+                state.insert(uid, synced_as(&href, etag, &tasks[idx]));
+                report.pushed += 1;
+            }
+            Written::Conflict => report.conflicts += 1,
+        }
+    }
+
+    // Pulled changes go through the hooks like a modify
+    storage.persist_with_hooks(HookEvent::Modify, renumber(tasks))?;
+    save_json(state_file, &state)?;
+
+    Ok(report)
+}
+
+/// Servers aren't required to return the ETag of a write, an empty one is different from any
+/// ETag so the resource is read again on the next sync.
+fn synced_as(href: &str, etag: Option<String>, task: &Task) -> Synced {
+    Synced {
+        href: href.to_string(),
+        etag: etag.unwrap_or_default(),
+        line: task.to_string(),
+    }
+}
+
+fn renumber(tasks: TaskList) -> TaskList {
+    tasks
+        .into_iter()
+        .enumerate()
+        .map(|(idx, task)| Task {
+            id: idx + 1,
+            ..task
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use tiny_http::{Header, Response, Server};
+
+    use super::{CalDavClient, SyncReport, from_calendar, sync, to_calendar};
+    use crate::{
+        config::CalDavConfig,
+        storage::TaskStorage,
+        tasks::{error::TaskError, task::Task},
+    };
+
+    /// The calendar objects of the stand-in server by href, with their ETag.
+    type Collection = Arc<Mutex<BTreeMap<String, (u32, String)>>>;
+
+    const COLLECTION: &str = "/calendars/me/tasks/";
+
+    fn serve() -> (String, Collection) {
+        serve_with(false)
+    }
+
+    /// A minimal CalDAV server: PROPFIND of the collection and conditional GET/PUT/DELETE of its
+    /// objects, ETags are a counter incremented on each write. The PROPFIND hrefs are paths, or
+    /// absolute URLs with `absolute_hrefs`.
+    fn serve_with(absolute_hrefs: bool) -> (String, Collection) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let origin = format!("http://{}", server.server_addr());
+        let url = format!("{origin}{COLLECTION}");
+        let collection = Collection::default();
+        let prefix = if absolute_hrefs {
+            origin
+        } else {
+            String::new()
+        };
+
+        let objects = collection.clone();
+        std::thread::spawn(move || {
+            let mut next_etag = 1;
+
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let header = |name: &'static str| {
+                    request
+                        .headers()
+                        .iter()
+                        .find(|header| header.field.equiv(name))
+                        .map(|header| header.value.to_string())
+                };
+                let href = request.url().to_string();
+
+                let mut objects = objects.lock().unwrap();
+                let etag = objects.get(&href).map(|(etag, _)| format!("\"{etag}\""));
+                let precondition = match (header("If-Match"), header("If-None-Match")) {
+                    (Some(expected), _) => etag.as_ref() == Some(&expected),
+                    (_, Some(_)) => etag.is_none(),
+                    _ => true,
+                };
+
+                let response = match request.method().as_str() {
+                    "PROPFIND" => {
+                        let responses: String = objects
+                            .iter()
+                            .map(|(href, (etag, _))| {
+                                format!(
+                                    "<d:response><d:href>{prefix}{href}</d:href><d:propstat><d:prop><d:getetag>\"{etag}\"</d:getetag></d:prop></d:propstat></d:response>"
+                                )
+                            })
+                            .collect();
+                        let body = format!(
+                            "<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\"><d:response><d:href>{COLLECTION}</d:href><d:propstat><d:prop/></d:propstat></d:response>{responses}</d:multistatus>"
+                        );
+                        Response::from_string(body).with_status_code(207)
+                    }
+                    "GET" => match objects.get(&href) {
+                        Some((_, ics)) => Response::from_string(ics.clone())
+                            .with_header(etag_header(etag.as_deref().unwrap())),
+                        None => Response::from_string("").with_status_code(404),
+                    },
+                    _ if !precondition => Response::from_string("").with_status_code(412),
+                    "PUT" => {
+                        objects.insert(href, (next_etag, body));
+                        next_etag += 1;
+                        Response::from_string("")
+                            .with_status_code(201)
+                            .with_header(etag_header(&format!("\"{}\"", next_etag - 1)))
+                    }
+                    "DELETE" => {
+                        objects.remove(&href);
+                        Response::from_string("").with_status_code(204)
+                    }
+                    _ => Response::from_string("").with_status_code(405),
+                };
+
+                let _ = request.respond(response);
+            }
+        });
+
+        (url, collection)
+    }
+
+    fn etag_header(etag: &str) -> Header {
+        Header::from_bytes("ETag", etag).unwrap()
+    }
+
+    struct Local {
+        _dir: tempfile::TempDir,
+        storage: TaskStorage,
+        state_file: std::path::PathBuf,
+        todo_file: std::path::PathBuf,
+    }
+
+    impl Local {
+        fn new(content: &str) -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let todo_file = dir.path().join("todo.txt");
+            std::fs::write(&todo_file, content).unwrap();
+
+            Self {
+                storage: TaskStorage::new(todo_file.clone()),
+                state_file: dir.path().join(".caldav.json"),
+                todo_file,
+                _dir: dir,
+            }
+        }
+
+        fn sync(&self, client: &CalDavClient) -> Result<SyncReport, TaskError> {
+            sync(&self.storage, client, &self.state_file)
+        }
+
+        fn tasks(&self) -> Vec<Task> {
+            self.storage.get_all().unwrap()
+        }
+
+        fn write(&self, content: &str) {
+            std::fs::write(&self.todo_file, content).unwrap();
+        }
+    }
+
+    fn client(url: &str) -> CalDavClient {
+        CalDavClient::new(&CalDavConfig {
+            url: Some(url.to_string()),
+            ..CalDavConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn remote_task(collection: &Collection, uid: &str) -> Task {
+        let objects = collection.lock().unwrap();
+        let (_, ics) = &objects[&format!("{COLLECTION}{uid}.ics")];
+
+        from_calendar(ics).unwrap()
+    }
+
+    fn edit_remote(collection: &Collection, task: &str) {
+        let task = Task::from_str(0, task).unwrap();
+        let href = format!("{COLLECTION}{}.ics", task.stable_id().unwrap());
+
+        let mut objects = collection.lock().unwrap();
+        let etag = objects.values().map(|(etag, _)| *etag).max().unwrap_or(0) + 100;
+        objects.insert(href, (etag, to_calendar(&task, chrono::Utc::now())));
+    }
+
+    fn task(line: &str) -> Task {
+        Task::from_str(0, line).unwrap()
+    }
+
+    #[test]
+    fn it_pushes_new_tasks_with_a_stable_id() {
+        let (url, collection) = serve();
+        let local = Local::new("call mom +family\nbuy milk id:4\n");
+
+        let report = local.sync(&client(&url)).unwrap();
+
+        assert_eq!(
+            report,
+            SyncReport {
+                pushed: 2,
+                ..SyncReport::default()
+            }
+        );
+        assert_eq!(remote_task(&collection, "5"), task("call mom +family id:5"));
+        assert_eq!(remote_task(&collection, "4"), task("buy milk id:4"));
+        assert_eq!(local.tasks()[0].stable_id(), Some("5"));
+
+        // Nothing changed since
+        assert_eq!(local.sync(&client(&url)).unwrap(), SyncReport::default());
+    }
+
+    #[test]
+    fn it_matches_absolute_hrefs_with_the_synced_tasks() {
+        let (url, collection) = serve_with(true);
+        let local = Local::new("call mom id:1\n");
+        local.sync(&client(&url)).unwrap();
+
+        edit_remote(&collection, "(A) call mom id:1");
+
+        assert_eq!(
+            local.sync(&client(&url)).unwrap(),
+            SyncReport {
+                pulled: 1,
+                ..SyncReport::default()
+            }
+        );
+        assert_eq!(local.sync(&client(&url)).unwrap(), SyncReport::default());
+        assert_eq!(local.tasks().len(), 1);
+    }
+
+    #[test]
+    fn it_pulls_remote_changes() {
+        let (url, collection) = serve();
+        let local = Local::new("call mom id:1\nbuy milk id:2\n");
+        local.sync(&client(&url)).unwrap();
+
+        edit_remote(&collection, "(A) call mom @phone id:1 due:2024-06-10");
+        edit_remote(&collection, "water the plants id:7");
+        collection
+            .lock()
+            .unwrap()
+            .remove(&format!("{COLLECTION}2.ics"));
+
+        let report = local.sync(&client(&url)).unwrap();
+
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled: 2,
+                deleted: 1,
+                ..SyncReport::default()
+            }
+        );
+        let tasks = local.tasks();
+        assert_eq!(tasks.len(), 2);
+        assert!(crate::merge::same(
+            &tasks[0],
+            &task("(A) call mom @phone id:1 due:2024-06-10")
+        ));
+        assert!(crate::merge::same(
+            &tasks[1],
+            &task("water the plants id:7")
+        ));
+    }
+
+    #[test]
+    fn it_merges_tasks_changed_on_both_sides() {
+        let (url, collection) = serve();
+        let local = Local::new("call mom id:1\n");
+        local.sync(&client(&url)).unwrap();
+
+        local.write("call mom id:1 due:2024-06-10\n");
+        edit_remote(&collection, "(A) call mom id:1");
+
+        let report = local.sync(&client(&url)).unwrap();
+
+        assert_eq!(
+            report,
+            SyncReport {
+                pushed: 1,
+                ..SyncReport::default()
+            }
+        );
+        let merged = task("(A) call mom id:1 due:2024-06-10");
+        assert!(crate::merge::same(&local.tasks()[0], &merged));
+        assert!(crate::merge::same(&remote_task(&collection, "1"), &merged));
+    }
+
+    #[test]
+    fn it_keeps_edits_over_deletions() {
+        let (url, collection) = serve();
+        let local = Local::new("call mom id:1\nbuy milk id:2\n");
+        local.sync(&client(&url)).unwrap();
+
+        local.write("buy oat milk id:2\n");
+        edit_remote(&collection, "call mom @phone id:1");
+        collection
+            .lock()
+            .unwrap()
+            .remove(&format!("{COLLECTION}2.ics"));
+
+        let report = local.sync(&client(&url)).unwrap();
+
+        assert_eq!(
+            report,
+            SyncReport {
+                pushed: 1,
+                pulled: 1,
+                ..SyncReport::default()
+            }
+        );
+        assert_eq!(local.tasks().len(), 2);
+        assert!(crate::merge::same(
+            &remote_task(&collection, "2"),
+            &task("buy oat milk id:2")
+        ));
+    }
+
+    #[test]
+    fn it_reports_conflicts_without_changing_either_side() {
+        let (url, collection) = serve();
+        let local = Local::new("call mom id:1\n");
+        local.sync(&client(&url)).unwrap();
+
+        local.write("call dad id:1\n");
+        edit_remote(&collection, "call grandma id:1");
+
+        let report = local.sync(&client(&url)).unwrap();
+
+        assert_eq!(
+            report,
+            SyncReport {
+                conflicts: 1,
+                ..SyncReport::default()
+            }
+        );
+        assert!(crate::merge::same(
+            &local.tasks()[0],
+            &task("call dad id:1")
+        ));
+        assert!(crate::merge::same(
+            &remote_task(&collection, "1"),
+            &task("call grandma id:1")
+        ));
+    }
+
+    #[test]
+    fn it_requires_a_url() {
+        assert!(matches!(
+            CalDavClient::new(&CalDavConfig::default()),
+            Err(TaskError::CalDavNotConfigured)
+        ));
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::{
+    formats::{escape_ics, fold_ics, vtodo},
+    tasks::{error::TaskError, task::Task},
+};
+
+/// The whole todo.txt line, the fields VTODO can't represent are taken from it.
+const LINE_PROPERTY: &str = "X-TODORS-LINE";
+
+/// A calendar holding the VTODO of `task`, the resource stored for it on the server.
+pub fn to_calendar(task: &Task, now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//todors//todors//EN".to_string(),
+    ];
+
+    let mut todo = vtodo(task, now);
+    // The UID on the server is the bare `id:` tag, it's read back as the tag by `from_calendar`
+    if let (Some(id), Some(uid)) = (
+        task.stable_id(),
+        todo.iter_mut().find(|line| line.starts_with("UID:")),
+    ) {
+        *uid = format!("UID:{id}");
+    }
+    let end = todo.pop();
+    todo.push(format!("{LINE_PROPERTY}:{}", escape_ics(&task.to_string())));
+    todo.extend(end);
+
+    lines.extend(todo);
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_ics(line)).collect()
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+/// The properties of the first VTODO of `ics` by name, without their parameters.
+fn properties(ics: &str) -> HashMap<String, String> {
+    // Folded lines continue with a space or a tab
+    let unfolded = ics
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+
+    unfolded
+        .lines()
+        .skip_while(|line| *line != "BEGIN:VTODO")
+        .take_while(|line| *line != "END:VTODO")
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.split(';').next().unwrap_or(name);
+
+            Some((name.to_ascii_uppercase(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Dates and date-times both start with `YYYYMMDD`.
+fn date(value: Option<&String>) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value?.get(..8)?, "%Y%m%d").ok()
+}
+
+/// The local date and time of a `DUE`, without time for a date. A UTC date-time, ending with
+/// `Z`, is converted to local time.
+fn due(value: Option<&String>) -> (Option<NaiveDate>, Option<NaiveTime>) {
+    let Some(value) = value else {
+        return (None, None);
+    };
+
+    match NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S") {
+        Ok(due) if value.ends_with('Z') => {
+            let due = Utc
+                .from_utc_datetime(&due)
+                .with_timezone(&Local)
+                .naive_local();
+            (Some(due.date()), Some(due.time()))
+        }
+        Ok(due) => (Some(due.date()), Some(due.time())),
+        Err(_) => (date(Some(value)), None),
+    }
+}
+
+/// Read back a task from its VTODO. The todo.txt line stored with it is the starting point, the
+/// fields VTODO represents are then taken from the VTODO since other apps only change those.
+pub fn from_calendar(ics: &str) -> Result<Task, TaskError> {
+    let properties = properties(ics);
+    let Some(uid) = properties.get("UID") else {
+        eprintln!("A VTODO doesn't have a UID");
+        return Err(TaskError::InvalidServerResponse);
+    };
+
+    let mut task = match properties.get(LINE_PROPERTY) {
+        Some(line) => Task::from_str(0, &unescape(line))?,
+        None => Task {
+            created_at: date(properties.get("CREATED")),
+            ..Task::default()
+        },
+    };
+
+    let summary = unescape(properties.get("SUMMARY").map_or("", String::as_str));
+    let summary = Task::from_subject(0, summary.trim())?;
+    task.subject = summary.subject;
+    task.contexts = summary.contexts;
+    task.projects = summary.projects;
+    task.hashtags = summary.hashtags;
+
+    let (due_date, due_time) = due(properties.get("DUE"));
+    task.due_date = due_date;
+    // An `at:` tag is kept as long as the time didn't change
+    if due_time != task.due_time_of_day() {
+        task.tags.remove("at");
+        task.due_time = due_time;
+    }
+    task.priority = match properties.get("PRIORITY").map(|value| value.parse::<u8>()) {
+        Some(Ok(priority @ 1..=9)) => Some((b'A' + priority - 1) as char),
+        _ => None,
+    };
+
+    task.completed = properties
+        .get("STATUS")
+        .is_some_and(|status| status == "COMPLETED");
+    task.completed_at = date(properties.get("COMPLETED")).filter(|_| task.completed);
+    if task.completed {
+        task.priority = None;
+    }
+
+    task.tags.insert("id".to_string(), uid.clone());
+
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{from_calendar, to_calendar};
+    use crate::tasks::task::Task;
+
+    #[test]
+    fn it_round_trips_tasks() {
+        let task = Task::from_str(
+            0,
+            "(B) 2024-06-01 call mom, then dad @phone +family due:2024-06-10 id:7 energy:low",
+        )
+        .unwrap();
+
+        assert_eq!(
+            from_calendar(&to_calendar(&task, Utc::now())).unwrap(),
+            task
+        );
+    }
+
+    #[test]
+    fn it_uses_the_bare_id_as_uid() {
+        let task = Task::from_str(0, "call mom id:7").unwrap();
+
+        assert!(to_calendar(&task, Utc::now()).contains("\r\nUID:7\r\n"));
+    }
+
+    #[test]
+    fn it_keeps_the_fields_vtodo_cant_represent() {
+        let task = Task::from_str(0, "(B) 2024-06-01 call mom id:7 energy:low").unwrap();
+        let edited = to_calendar(&task, Utc::now())
+            .replace("SUMMARY:call mom", "SUMMARY:call mom @phone")
+            .replace(
+                "STATUS:NEEDS-ACTION",
+                "STATUS:COMPLETED\r\nCOMPLETED:20240602T090000Z",
+            );
+
+        assert_eq!(
+            from_calendar(&edited).unwrap(),
+            Task::from_str(0, "x 2024-06-02 2024-06-01 call mom @phone id:7 energy:low").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_reads_vtodos_created_by_other_apps() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:9f3a-11\r\nSUMMARY:Water\r\n  the plants\r\nPRIORITY:1\r\nDUE;VALUE=DATE:20240610\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+        assert_eq!(
+            from_calendar(ics).unwrap(),
+            Task::from_str(0, "(A) Water the plants due:2024-06-10 id:9f3a-11").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_reads_the_summary_as_a_subject_only() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:9f3a-12\r\nSUMMARY:x marks the spot @map\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+        let task = from_calendar(ics).unwrap();
+        assert_eq!(task.subject, "x marks the spot @map");
+        assert_eq!(task.contexts, ["map"]);
+        assert!(!task.completed);
+    }
+
+    #[test]
+    fn it_round_trips_due_times() {
+        let task = Task::from_str(0, "dentist due:2024-06-10 at:14:30 id:7").unwrap();
+        let ics = to_calendar(&task, Utc::now());
+        assert!(ics.contains("DUE:20240610T143000\r\n"));
+        assert_eq!(from_calendar(&ics).unwrap(), task);
+
+        let moved = ics.replace("DUE:20240610T143000", "DUE:20240611T090000");
+        assert_eq!(
+            from_calendar(&moved).unwrap(),
+            Task::from_str(0, "dentist due:2024-06-11T09:00 id:7").unwrap()
+        );
+    }
+}
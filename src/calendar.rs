@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use colored::Colorize;
+
+use crate::tasks::task::Task;
+
+/// The tasks due and completed on a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Day {
+    /// The open tasks due that day
+    pub due: usize,
+    pub completed: usize,
+}
+
+const CELL_WIDTH: usize = 8;
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("Every month has a first day")
+}
+
+/// The days of the month of `month` with tasks due or completed.
+pub fn month_days(tasks: &[Task], month: NaiveDate) -> BTreeMap<NaiveDate, Day> {
+    let first = first_of_month(month);
+    let in_month = |date: &NaiveDate| first_of_month(*date) == first;
+
+    let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+    for task in tasks {
+        if let Some(due) = task.due_date.filter(in_month)
+            && !task.completed
+        {
+            days.entry(due).or_default().due += 1;
+        }
+
+        if let Some(completed) = task.completed_at.filter(in_month)
+            && task.completed
+        {
+            days.entry(completed).or_default().completed += 1;
+        }
+    }
+
+    days
+}
+
+/// A month grid starting on Monday, each day followed by the number of tasks due and, with
+/// `with_completed`, completed that day. Today is highlighted, as are the days with overdue tasks.
+pub fn month_grid(
+    days: &BTreeMap<NaiveDate, Day>,
+    month: NaiveDate,
+    today: NaiveDate,
+    with_completed: bool,
+) -> Vec<String> {
+    let first = first_of_month(month);
+    let next_month = first + Months::new(1);
+
+    let title = first.format("%B %Y").to_string();
+    let mut lines = vec![
+        format!("{title:^width$}", width = CELL_WIDTH * 7)
+            .trim_end()
+            .to_string(),
+        ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+            .map(|name| format!("{name:<CELL_WIDTH$}"))
+            .concat()
+            .trim_end()
+            .to_string(),
+    ];
+
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let mut line = " ".repeat(offset * CELL_WIDTH);
+    let mut date = first;
+    while date < next_month {
+        let day = days.get(&date).cloned().unwrap_or_default();
+
+        let mut marks = String::new();
+        if day.due > 0 {
+            marks.push_str(&day.due.to_string());
+        }
+        if with_completed && day.completed > 0 {
+            marks.push_str(&format!("✓{}", day.completed));
+        }
+
+        let cell = format!("{:>2} {marks}", date.day());
+        let padding = " ".repeat(CELL_WIDTH.saturating_sub(cell.chars().count()));
+        let cell = if date == today {
+            cell.reversed().bold().to_string()
+        } else if date < today && day.due > 0 {
+            cell.red().bold().to_string()
+        } else if day.due > 0 {
+            cell.yellow().to_string()
+        } else {
+            cell
+        };
+        line.push_str(&cell);
+        line.push_str(&padding);
+
+        if date.weekday().num_days_from_monday() == 6 {
+            lines.push(std::mem::take(&mut line).trim_end().to_string());
+        }
+        date = date + Days::new(1);
+    }
+    if !line.trim().is_empty() {
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines
+}
+
+/// The open tasks due in the `days` days from `today`, by date, after the overdue ones.
+pub fn agenda(tasks: &[Task], days: u64, today: NaiveDate) -> Vec<(String, Vec<&Task>)> {
+    let end = today
+        .checked_add_days(Days::new(days))
+        .unwrap_or(NaiveDate::MAX);
+
+    let mut overdue = Vec::new();
+    let mut by_date: BTreeMap<NaiveDate, Vec<&Task>> = BTreeMap::new();
+    for task in tasks.iter().filter(|task| !task.completed) {
+        match task.due_date {
+            Some(due) if due < today => overdue.push(task),
+            Some(due) if due < end => by_date.entry(due).or_default().push(task),
+            _ => {}
+        }
+    }
+    // On a given day the tasks without a due time come first, like in `due_key`
+    overdue.sort_by_key(|task| task.due_key());
+    for tasks in by_date.values_mut() {
+        tasks.sort_by_key(|task| task.due_time_of_day());
+    }
+
+    let overdue = Some(("Overdue".to_string(), overdue)).filter(|(_, tasks)| !tasks.is_empty());
+    overdue
+        .into_iter()
+        .chain(
+            by_date
+                .into_iter()
+                .map(|(date, tasks)| (date.format("%Y-%m-%d %A").to_string(), tasks)),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Day, agenda, month_days, month_grid};
+    use crate::tasks::task::Task;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        [
+            "call the bank due:2024-06-03",
+            "pay rent due:2024-06-03",
+            "x 2024-06-03 2024-06-01 send invoice due:2024-06-03",
+            "book flights due:2024-06-20",
+            "renew passport due:2024-07-01",
+            "no due date",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn it_counts_the_tasks_of_each_day() {
+        let days = month_days(&tasks(), date(15));
+
+        assert_eq!(
+            days.into_iter().collect::<Vec<_>>(),
+            [
+                (
+                    date(3),
+                    Day {
+                        due: 2,
+                        completed: 1
+                    }
+                ),
+                (
+                    date(20),
+                    Day {
+                        due: 1,
+                        completed: 0
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_renders_a_month_grid() {
+        colored::control::set_override(false);
+
+        let grid = month_grid(&month_days(&tasks(), date(1)), date(1), date(10), true);
+
+        assert_eq!(
+            grid,
+            [
+                "                       June 2024",
+                "Mo      Tu      We      Th      Fr      Sa      Su",
+                "                                         1       2",
+                " 3 2✓1   4       5       6       7       8       9",
+                "10      11      12      13      14      15      16",
+                "17      18      19      20 1    21      22      23",
+                "24      25      26      27      28      29      30",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_lists_the_agenda_after_the_overdue_tasks() {
+        let tasks = tasks();
+
+        let agenda: Vec<(String, Vec<usize>)> = agenda(&tasks, 20, date(10))
+            .into_iter()
+            .map(|(header, tasks)| (header, tasks.iter().map(|task| task.id).collect()))
+            .collect();
+
+        assert_eq!(
+            agenda,
+            [
+                ("Overdue".to_string(), vec![1, 2]),
+                ("2024-06-20 Thursday".to_string(), vec![4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_lists_the_agenda_up_to_the_latest_date() {
+        let tasks = tasks();
+
+        assert_eq!(agenda(&tasks, u64::MAX, date(10)).len(), 3);
+    }
+}
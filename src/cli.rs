@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
-use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
+use chrono::{Datelike, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(
@@ -13,10 +13,28 @@ pub struct Cli {
     #[arg(long = "config", short = 'c', help = "Path to the config file.")]
     pub config_path: Option<PathBuf>,
 
+    #[arg(
+        long = "set",
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value,
+        global = true,
+        help = "Override a config key, takes precedence over the config file and env vars."
+    )]
+    pub overrides: Vec<(String, String)>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("invalid KEY=VALUE: no `=` found in `{s}`")),
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     Add(Add),
@@ -25,10 +43,27 @@ pub enum Commands {
     Remove(Remove),
     Edit(Edit),
     Due(Due),
+    Cal(Cal),
     Undone(Undone),
     Clean(Clean),
     Modify(Modify),
     Next(Next),
+    Tui(Tui),
+    Import(Import),
+    Export(Export),
+    Sync(Sync),
+    Caldav(Caldav),
+    Merge(Merge),
+    Serve(Serve),
+    Start(Start),
+    Stop(Stop),
+    Report(Report),
+    Stats(Stats),
+    Remind(Remind),
+    Config(ConfigArgs),
+    // Any other command, resolved from the aliases or the `todors-<name>` plugins
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 #[derive(Parser)]
@@ -74,6 +109,19 @@ pub struct List {
         default_value_t = false
     )]
     pub all: bool,
+
+    #[arg(long, help = "Group the tasks by their first project or context")]
+    pub by: Option<GroupBy>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Only the most urgent tasks whose remaining estimate fits in this time, e.g. `6h`"
+    )]
+    pub capacity: Option<String>,
+
+    #[arg(long, help = "Keep running and redraw whenever the todo file changes")]
+    pub watch: bool,
 }
 
 #[derive(Parser)]
@@ -91,15 +139,55 @@ pub struct Remove {
 #[command(
     name = "edit",
     visible_alias = "e",
-    about = "Edit the todo file with a text editor"
+    about = "Edit the todo file, or only the selected tasks, with a text editor"
 )]
 pub struct Edit {
-    pub item: Option<u32>,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub query: Option<Vec<String>>,
 }
 
 #[derive(Parser)]
 #[command(name = "due", about = "List all due tasks")]
-pub struct Due;
+pub struct Due {
+    #[arg(long, help = "Keep running and redraw whenever the todo file changes")]
+    pub watch: bool,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "cal",
+    about = "Show the tasks due each day of a month, or the coming ones"
+)]
+pub struct Cal {
+    #[arg(
+        value_parser = parse_month,
+        help = "The month to show, as `2024-06` or a month number of this year, defaults to this month"
+    )]
+    pub month: Option<NaiveDate>,
+
+    #[arg(long, help = "Also count the tasks completed each day")]
+    pub completed: bool,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        num_args = 0..=1,
+        default_missing_value = "7",
+        conflicts_with_all = ["month", "completed"],
+        help = "List the tasks due in the next days, 7 by default, under their date"
+    )]
+    pub agenda: Option<u64>,
+}
+
+/// The first day of a month given as `2024-06`, or as a month number of the current year.
+fn parse_month(value: &str) -> Result<NaiveDate, String> {
+    let month = match value.parse::<u32>() {
+        Ok(month) => NaiveDate::from_ymd_opt(chrono::Local::now().year(), month, 1),
+        Err(_) => NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d").ok(),
+    };
+
+    month.ok_or_else(|| format!("`{value}` isn't a month, expected e.g. `2024-06` or `6`"))
+}
 
 #[derive(Parser)]
 #[command(
@@ -137,6 +225,29 @@ pub struct Modify {
 
     #[arg(long, conflicts_with = "due_date")]
     pub rm_due_date: bool,
+
+    #[arg(
+        long,
+        value_name = "NUMBER",
+        conflicts_with = "rm_parent",
+        help = "Make the tasks subtasks of this one"
+    )]
+    pub parent: Option<usize>,
+
+    #[arg(long, conflicts_with = "parent")]
+    pub rm_parent: bool,
+
+    #[arg(
+        long,
+        value_name = "NUMBERS",
+        value_delimiter = ',',
+        conflicts_with = "rm_dep",
+        help = "Mark the tasks as blocked by these ones, e.g. `--dep 3,5`"
+    )]
+    pub dep: Vec<usize>,
+
+    #[arg(long, conflicts_with = "dep")]
+    pub rm_dep: bool,
 }
 
 #[derive(Parser)]
@@ -147,4 +258,286 @@ pub struct Modify {
 pub struct Next {
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub query: Option<Vec<String>>,
+
+    #[arg(long, help = "Keep running and redraw whenever the todo file changes")]
+    pub watch: bool,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "tui",
+    about = "Browse and edit tasks in an interactive terminal interface"
+)]
+pub struct Tui;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    TodoTxt,
+    Sqlite,
+    /// The output of `task export`
+    TaskwarriorJson,
+    /// A Todoist project exported as CSV
+    TodoistCsv,
+    /// `- [ ]` and `- [x]` checklist items
+    Markdown,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "import",
+    about = "Append the tasks of another list to this one"
+)]
+pub struct Import {
+    #[arg(long, value_enum, default_value = "todo-txt")]
+    pub from: ImportFormat,
+
+    pub file: PathBuf,
+
+    #[arg(
+        long,
+        help = "Print the imported tasks as todo.txt lines without saving them"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Use the Markdown heading above each item as its project, e.g. `## Release 2.0` as +Release-2.0"
+    )]
+    pub headings_as_projects: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    TodoTxt,
+    Sqlite,
+    /// iCalendar VTODO entries
+    Ics,
+    /// `- [ ]` and `- [x]` checklist
+    Markdown,
+    /// HTML checklist fragment
+    Html,
+}
+
+#[derive(Parser)]
+#[command(name = "export", about = "Export tasks, to stdout or a file")]
+pub struct Export {
+    #[arg(long, value_enum, default_value = "todo-txt")]
+    pub to: ExportFormat,
+
+    #[arg(short, long, help = "Write to this file instead of stdout")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, help = "Group the Markdown and HTML checklists by project")]
+    pub by_project: bool,
+
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub query: Option<Vec<String>>,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "sync",
+    about = "Commit the todo files, pull the remote changes and push them back with git"
+)]
+pub struct Sync;
+
+#[derive(Parser)]
+#[command(
+    name = "caldav",
+    about = "Sync the tasks with the CalDAV collection set as `caldav.url` in the config",
+    after_help = "Each task is stored on the server under its `id:` tag, the tasks without one are \
+given one the first time they're pushed."
+)]
+pub struct Caldav;
+
+#[derive(Parser)]
+#[command(
+    name = "merge",
+    about = "Three-way merge of todo.txt files, keeping the changes made on each side",
+    after_help = "To use it as a git merge driver:
+  git config merge.todors.driver 'todors merge %O %A %B'
+  echo 'todo.txt merge=todors' >> .gitattributes"
+)]
+pub struct Merge {
+    #[arg(help = "The common ancestor of both versions")]
+    pub base: PathBuf,
+
+    #[arg(help = "Our version, replaced by the merged file")]
+    pub ours: PathBuf,
+
+    #[arg(help = "Their version")]
+    pub theirs: PathBuf,
+
+    #[arg(
+        short = 'p',
+        long,
+        help = "Print the merged file instead of replacing <OURS>"
+    )]
+    pub stdout: bool,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "serve",
+    about = "Serve a JSON API to read and update the tasks over HTTP"
+)]
+pub struct Serve {
+    #[arg(
+        long,
+        help = "Address to listen on, defaults to the `server.bind` config key"
+    )]
+    pub bind: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "start",
+    about = "Start a timer on a task, the elapsed time is added to its `spent:` tag when stopped"
+)]
+pub struct Start {
+    #[arg(help = "The number of the task, a running timer is stopped first")]
+    pub task: usize,
+}
+
+#[derive(Parser)]
+#[command(name = "stop", about = "Stop the running timer")]
+pub struct Stop;
+
+#[derive(Parser)]
+#[command(
+    name = "report",
+    about = "Summaries of the tasks, including the done archive"
+)]
+pub struct Report {
+    #[command(subcommand)]
+    pub report: ReportKind,
+}
+
+#[derive(Subcommand)]
+pub enum ReportKind {
+    Time(TimeReport),
+    Burndown(BurndownReport),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum GroupBy {
+    Project,
+    Context,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "time",
+    about = "Time spent on the tasks, from their `spent:` tag"
+)]
+pub struct TimeReport {
+    #[arg(long, help = "Sum the time per project or context instead of per task")]
+    pub by: Option<GroupBy>,
+
+    #[arg(
+        long,
+        help = "Only count the tasks completed since this date, open tasks are always counted"
+    )]
+    pub since: Option<NaiveDate>,
 }
+
+/// The longest period reports go back to, about ten years.
+const MAX_DAYS: u64 = 3660;
+
+#[derive(Parser)]
+#[command(name = "burndown", about = "Open tasks at the end of each day")]
+pub struct BurndownReport {
+    #[arg(
+        long,
+        default_value_t = 14,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_DAYS),
+        help = "Number of days to show, up to today"
+    )]
+    pub days: u64,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "stats",
+    about = "Created and completed tasks over time, lead time and overdue tasks, including the done archive"
+)]
+pub struct Stats {
+    #[arg(
+        long,
+        default_value_t = 14,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_DAYS),
+        help = "Number of days of daily activity, up to today"
+    )]
+    pub days: u64,
+
+    #[arg(
+        long,
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_DAYS / 7),
+        help = "Number of weeks of weekly activity"
+    )]
+    pub weeks: u64,
+
+    #[arg(
+        long,
+        default_value = "project",
+        help = "Break the tasks down per project or context"
+    )]
+    pub by: GroupBy,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "remind",
+    about = "Notify of the tasks due today, overdue or whose `remind:` time has come, once each",
+    after_help = "Notifications are sent with the `remind.command` config key, `notify-send` by default.
+To check every 5 minutes with cron:
+  */5 * * * * todors remind"
+)]
+pub struct Remind {
+    #[arg(long, help = "Keep running and check again periodically")]
+    pub watch: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        requires = "watch",
+        help = "Time between two checks, defaults to the `remind.interval` config key"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Parser)]
+#[command(name = "config", about = "Bootstrap and inspect the configuration")]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    Init(ConfigInit),
+    Show(ConfigShow),
+    Path(ConfigPath),
+}
+
+#[derive(Parser)]
+#[command(
+    name = "init",
+    about = "Create the config file, the todo directory and an empty todo.txt file"
+)]
+pub struct ConfigInit {
+    #[arg(long, help = "Directory where todo.txt is stored")]
+    pub todo_dir: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite the config file if it already exists")]
+    pub force: bool,
+}
+
+#[derive(Parser)]
+#[command(name = "show", about = "Print the effective configuration")]
+pub struct ConfigShow;
+
+#[derive(Parser)]
+#[command(name = "path", about = "Print the path of the config file")]
+pub struct ConfigPath;
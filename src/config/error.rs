@@ -0,0 +1,47 @@
+use std::{fmt::Display, path::PathBuf};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    HomeDirNotFound,
+    FailedToRead(PathBuf, std::io::Error),
+    FailedToParse(PathBuf, toml::de::Error),
+    InvalidOverride(toml::de::Error),
+    FailedToSerialize(toml::ser::Error),
+    FailedToWrite(PathBuf, std::io::Error),
+    AlreadyExists(PathBuf),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::HomeDirNotFound => f.write_str("Failed to find the home directory"),
+            ConfigError::FailedToRead(path, err) => {
+                write!(f, "Failed to read config file {}: {err}", path.display())
+            }
+            ConfigError::FailedToParse(path, err) => {
+                write!(f, "Invalid config file {}: {err}", path.display())
+            }
+            ConfigError::InvalidOverride(err) => write!(f, "Invalid config override: {err}"),
+            ConfigError::FailedToSerialize(err) => write!(f, "Failed to serialize config: {err}"),
+            ConfigError::FailedToWrite(path, err) => {
+                write!(f, "Failed to write {}: {err}", path.display())
+            }
+            ConfigError::AlreadyExists(path) => write!(
+                f,
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::FailedToRead(_, err) | ConfigError::FailedToWrite(_, err) => Some(err),
+            ConfigError::FailedToParse(_, err) | ConfigError::InvalidOverride(err) => Some(err),
+            ConfigError::FailedToSerialize(err) => Some(err),
+            ConfigError::HomeDirNotFound | ConfigError::AlreadyExists(_) => None,
+        }
+    }
+}
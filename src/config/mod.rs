@@ -0,0 +1,465 @@
+pub mod error;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use error::ConfigError;
+
+/// Env vars overriding a config key. The `TODO_*` ones are the same as todo.txt-cli, the `TODORS_*`
+/// ones come last so they win when both are set.
+const ENV_OVERRIDES: [(&str, &str); 8] = [
+    ("TODO_DIR", "todo_dir"),
+    ("TODO_FILE", "todo_file"),
+    ("TODORS_TODO_DIR", "todo_dir"),
+    ("TODORS_TODO_FILE", "todo_file"),
+    ("TODORS_EDITOR", "editor"),
+    ("TODORS_BACKEND", "backend"),
+    ("TODORS_SERVER_TOKEN", "server.token"),
+    ("TODORS_CALDAV_PASSWORD", "caldav.password"),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageBackend {
+    /// `todo.txt` in the todo dir
+    #[default]
+    TodoTxt,
+    /// `todo.db` in the todo dir
+    Sqlite,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStrategy {
+    #[default]
+    Rebase,
+    Merge,
+}
+
+/// The `[git]` table, to keep the todo dir in a git repository.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitConfig {
+    /// Commit the todo files after each change
+    pub auto_commit: bool,
+    /// Remote name or url used by `todors sync`
+    pub remote: String,
+    /// How `todors sync` integrates the remote changes
+    pub sync: SyncStrategy,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            auto_commit: false,
+            remote: "origin".to_string(),
+            sync: SyncStrategy::default(),
+        }
+    }
+}
+
+/// The `[server]` table of `todors serve`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Address listened on without `--bind`
+    pub bind: String,
+    /// Required as `Authorization: Bearer <token>` when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:7878".to_string(),
+            token: None,
+        }
+    }
+}
+
+/// The `[caldav]` table of `todors caldav`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalDavConfig {
+    /// The calendar collection holding the tasks, e.g. `https://dav.example.com/calendars/me/tasks/`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// The `[remind]` table of `todors remind`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemindConfig {
+    /// Run with the title and the body of each notification as its last arguments
+    pub command: String,
+    /// Seconds between two checks with `--watch`
+    pub interval: u64,
+}
+
+impl Default for RemindConfig {
+    fn default() -> Self {
+        Self {
+            command: "notify-send".to_string(),
+            interval: 60,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default = "default_todo_dir")]
+    pub todo_dir: PathBuf,
+    pub todo_file: Option<PathBuf>,
+    #[serde(default)]
+    pub backend: StorageBackend,
+    pub editor: Option<String>,
+    /// Custom commands, e.g. `today = "list due:2024-06-01 #next"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Saved queries used with `@view:<name>`, e.g. `work = "+work @office"`. They use the query
+    /// syntax of the commands (words, `+project`, `@context`, `#hashtag`, `due:DATE`, `key:value`
+    /// and task numbers) and like the other terms, they widen the query instead of narrowing it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub views: BTreeMap<String, String>,
+    /// Commands run on events, e.g. `pre-add = ["require-project"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hooks: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub git: GitConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub caldav: CalDavConfig,
+    #[serde(default)]
+    pub remind: RemindConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            todo_dir: default_todo_dir(),
+            todo_file: None,
+            backend: StorageBackend::default(),
+            editor: None,
+            aliases: BTreeMap::new(),
+            views: BTreeMap::new(),
+            hooks: BTreeMap::new(),
+            git: GitConfig::default(),
+            server: ServerConfig::default(),
+            caldav: CalDavConfig::default(),
+            remind: RemindConfig::default(),
+        }
+    }
+}
+
+/// Use `~/.todo` when it already exists, like todo.txt-cli does, else follow the XDG spec.
+fn default_todo_dir() -> PathBuf {
+    let legacy = PathBuf::from("~/.todo");
+    if expand_home(&legacy).is_dir() {
+        return legacy;
+    }
+
+    match std::env::var("XDG_DATA_HOME") {
+        Ok(path) if !path.is_empty() => Path::new(&path).join("todors"),
+        _ => PathBuf::from("~/.local/share/todors"),
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::home_dir()) {
+        (Ok(rest), Some(home_path)) => home_path.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+impl Config {
+    /// The config file used when none is passed with `--config`.
+    pub fn default_path() -> Result<PathBuf, ConfigError> {
+        match std::env::var("XDG_CONFIG_HOME") {
+            Ok(path) if !path.is_empty() => Ok(Path::new(&path).join("todors/config.toml")),
+            _ => std::env::home_dir()
+                .map(|home_path| home_path.join(".config/todors/config.toml"))
+                .ok_or(ConfigError::HomeDirNotFound),
+        }
+    }
+
+    /// Load the config file, falling back to the defaults when it doesn't exist.
+    pub fn from_path(config_file_path: &Path) -> Result<Self, ConfigError> {
+        Self::load(config_file_path, &[])
+    }
+
+    /// Resolve the effective configuration, each layer overriding the previous one: defaults,
+    /// config file, env vars and finally the `--set key=value` flags.
+    pub fn load(
+        config_file_path: &Path,
+        overrides: &[(String, String)],
+    ) -> Result<Self, ConfigError> {
+        Self::load_with_env(config_file_path, overrides, |var| std::env::var(var).ok())
+    }
+
+    fn load_with_env(
+        config_file_path: &Path,
+        overrides: &[(String, String)],
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut table = match std::fs::read_to_string(config_file_path) {
+            Ok(content) => {
+                let parse_error =
+                    |err| ConfigError::FailedToParse(config_file_path.to_path_buf(), err);
+
+                // Deserialize the file on its own first so errors point to the right line
+                toml::from_str::<Config>(&content).map_err(parse_error)?;
+                toml::from_str::<Table>(&content).map_err(parse_error)?
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Table::new(),
+            Err(err) => {
+                return Err(ConfigError::FailedToRead(
+                    config_file_path.to_path_buf(),
+                    err,
+                ));
+            }
+        };
+
+        let env_layer: Vec<(&str, Value)> = ENV_OVERRIDES
+            .iter()
+            .filter_map(|(var, key)| {
+                let value = env(var).filter(|value| !value.is_empty())?;
+                Some((*key, Value::String(value)))
+            })
+            .collect();
+        apply_layer(&mut table, env_layer);
+
+        let flags_layer = overrides
+            .iter()
+            .map(|(key, value)| (key.as_str(), parse_value(value)))
+            .collect();
+        apply_layer(&mut table, flags_layer);
+
+        table.try_into().map_err(ConfigError::InvalidOverride)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        toml::to_string(self).map_err(ConfigError::FailedToSerialize)
+    }
+
+    pub fn todo_dir(&self) -> PathBuf {
+        expand_home(&self.todo_dir)
+    }
+
+    pub fn todo_file(&self) -> PathBuf {
+        match &self.todo_file {
+            Some(todo_file) => expand_home(todo_file),
+            None => self.todo_dir().join("todo.txt"),
+        }
+    }
+
+    pub fn database_file(&self) -> PathBuf {
+        self.todo_dir().join("todo.db")
+    }
+
+    /// The archive of completed tasks, kept in the todo dir like todo.txt-cli does.
+    pub fn done_file(&self) -> PathBuf {
+        self.todo_dir().join("done.txt")
+    }
+
+    /// The timer started by `todors start`.
+    pub fn timer_file(&self) -> PathBuf {
+        self.todo_dir().join(".timer.json")
+    }
+
+    /// The reminders already sent by `todors remind`.
+    pub fn remind_state_file(&self) -> PathBuf {
+        self.todo_dir().join(".remind.json")
+    }
+
+    /// What `todors caldav` knows of the collection since the last sync.
+    pub fn caldav_state_file(&self) -> PathBuf {
+        self.todo_dir().join(".caldav.json")
+    }
+}
+
+/// Set the keys of a layer over the ones below. `todo_dir` and `todo_file` are a single setting:
+/// a layer moving the todo dir without naming a file drops the `todo_file` of the layers below.
+fn apply_layer(table: &mut Table, layer: Vec<(&str, Value)>) {
+    let sets = |name: &str| layer.iter().any(|(key, _)| *key == name);
+    if sets("todo_dir") && !sets("todo_file") {
+        table.remove("todo_file");
+    }
+
+    for (key, value) in layer {
+        set_key(table, key, value);
+    }
+}
+
+/// Set a possibly dotted key (e.g. `aliases.today`), creating the intermediate tables.
+fn set_key(table: &mut Table, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let entry = table
+                .entry(head)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Table::new());
+            }
+            if let Value::Table(inner) = entry {
+                set_key(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Values given on the command line are parsed as TOML (`true`, `12`, `"quoted"`), anything
+/// that isn't valid TOML is used as a plain string.
+fn parse_value(value: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use super::error::ConfigError;
+    use std::path::PathBuf;
+
+    #[test]
+    fn it_returns_the_todo_file_name() {
+        let config = Config {
+            todo_dir: PathBuf::from("/home/test/.todo"),
+            ..Config::default()
+        };
+
+        assert!(config.todo_file().ends_with("/home/test/.todo/todo.txt"));
+    }
+
+    #[test]
+    fn it_support_tild_as_home_dir() {
+        let config = Config {
+            todo_dir: PathBuf::from("~/.todo"),
+            ..Config::default()
+        };
+
+        assert!(!config.todo_file().starts_with("~"));
+        assert!(config.todo_file().ends_with(".todo/todo.txt"));
+    }
+
+    #[test]
+    fn it_falls_back_to_defaults_when_the_file_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = Config::from_path(&dir.path().join("config.toml")).unwrap();
+
+        assert!(config.todo_file().ends_with("todo.txt"));
+        assert_eq!(config.editor, None);
+    }
+
+    #[test]
+    fn it_parses_the_config_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "todo_dir = \"/tmp/todo\"\neditor = \"code --wait\"\n",
+        )
+        .unwrap();
+
+        let config = Config::from_path(file.path()).unwrap();
+
+        assert_eq!(config.todo_dir, PathBuf::from("/tmp/todo"));
+        assert_eq!(config.editor.as_deref(), Some("code --wait"));
+    }
+
+    #[test]
+    fn it_parses_aliases_and_views() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "[aliases]\ntoday = \"list #today\"\n\n[views]\nwork = \"+work @office\"\n",
+        )
+        .unwrap();
+        let overrides = [("views.home".to_string(), "+home".to_string())];
+
+        let config = Config::load(file.path(), &overrides).unwrap();
+
+        assert_eq!(config.aliases["today"], "list #today");
+        assert_eq!(config.views["work"], "+work @office");
+        assert_eq!(config.views["home"], "+home");
+    }
+
+    #[test]
+    fn it_reports_unknown_keys_with_their_line() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "todo_dir = \"/tmp/todo\"\neditr = \"vim\"\n").unwrap();
+
+        let err = Config::from_path(file.path()).unwrap_err();
+
+        assert!(matches!(err, ConfigError::FailedToParse(..)));
+        let message = err.to_string();
+        assert!(message.contains("line 2"), "{message}");
+        assert!(message.contains("editr"), "{message}");
+    }
+
+    #[test]
+    fn it_overrides_the_file_with_env_vars_then_flags() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "todo_dir = \"/file\"\neditor = \"vim\"\n").unwrap();
+        let env = |var: &str| match var {
+            "TODO_DIR" => Some("/todo-txt-cli".to_string()),
+            "TODORS_TODO_DIR" => Some("/env".to_string()),
+            "TODORS_EDITOR" => Some("nano".to_string()),
+            _ => None,
+        };
+        let overrides = [("editor".to_string(), "code --wait".to_string())];
+
+        let config = Config::load_with_env(file.path(), &overrides, env).unwrap();
+
+        assert_eq!(config.todo_dir, PathBuf::from("/env"));
+        assert_eq!(config.editor.as_deref(), Some("code --wait"));
+    }
+
+    #[test]
+    fn it_uses_todo_file_over_todo_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = |var: &str| (var == "TODO_FILE").then(|| "/tmp/list.txt".to_string());
+
+        let config = Config::load_with_env(&dir.path().join("config.toml"), &[], env).unwrap();
+
+        assert_eq!(config.todo_file(), PathBuf::from("/tmp/list.txt"));
+    }
+
+    #[test]
+    fn it_drops_the_todo_file_of_a_lower_layer_when_the_todo_dir_is_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = |var: &str| (var == "TODO_FILE").then(|| "/tmp/list.txt".to_string());
+        let overrides = [("todo_dir".to_string(), "/flags".to_string())];
+
+        let config =
+            Config::load_with_env(&dir.path().join("config.toml"), &overrides, env).unwrap();
+
+        assert_eq!(config.todo_file, None);
+        assert_eq!(config.todo_file(), PathBuf::from("/flags/todo.txt"));
+    }
+
+    #[test]
+    fn it_reports_invalid_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let overrides = [("todo_dri".to_string(), "/tmp".to_string())];
+
+        let err = Config::load_with_env(&dir.path().join("config.toml"), &overrides, |_| None)
+            .unwrap_err();
+
+        assert!(matches!(err, ConfigError::InvalidOverride(_)));
+    }
+}
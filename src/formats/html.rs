@@ -0,0 +1,80 @@
+use super::group_by_project;
+use crate::tasks::task::Task;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn list(tasks: &[&Task]) -> String {
+    let mut html = String::from("<ul class=\"todors\">\n");
+
+    for task in tasks {
+        let checked = if task.completed { " checked" } else { "" };
+        html.push_str(&format!(
+            "  <li><input type=\"checkbox\" disabled{checked}> "
+        ));
+        if let Some(priority) = task.priority.filter(|_| !task.completed) {
+            html.push_str(&format!("<strong>({priority})</strong> "));
+        }
+        html.push_str(&escape(&task.subject));
+        if let Some(due_at) = task.due_at() {
+            html.push_str(&format!(
+                " <time datetime=\"{}\">due {}</time>",
+                due_at.format("%Y-%m-%dT%H:%M"),
+                due_at.format("%Y-%m-%d %H:%M")
+            ));
+        } else if let Some(due_date) = task.due_date {
+            html.push_str(&format!(
+                " <time datetime=\"{due_date}\">due {due_date}</time>"
+            ));
+        }
+        html.push_str("</li>\n");
+    }
+
+    html.push_str("</ul>\n");
+    html
+}
+
+/// An HTML fragment with a checklist, to embed in another page.
+pub fn to_html(tasks: &[Task], by_project: bool) -> String {
+    if !by_project {
+        return list(&tasks.iter().collect::<Vec<_>>());
+    }
+
+    group_by_project(tasks)
+        .into_iter()
+        .map(|(project, tasks)| match project {
+            Some(project) => format!("<h2>{}</h2>\n{}", escape(project), list(&tasks)),
+            None => list(&tasks),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_html;
+    use crate::tasks::task::Task;
+
+    #[test]
+    fn it_exports_an_escaped_checklist() {
+        let tasks = [
+            Task::from_str(1, "(A) fix <div> & co +web due:2024-06-10").unwrap(),
+            Task::from_str(2, "x 2024-06-02 2024-06-01 call mom").unwrap(),
+        ];
+
+        assert_eq!(
+            to_html(&tasks, true),
+            "<ul class=\"todors\">
+  <li><input type=\"checkbox\" disabled checked> call mom</li>
+</ul>
+<h2>web</h2>
+<ul class=\"todors\">
+  <li><input type=\"checkbox\" disabled> <strong>(A)</strong> fix &lt;div&gt; &amp; co +web <time datetime=\"2024-06-10\">due 2024-06-10</time></li>
+</ul>
+"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::tasks::task::Task;
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 bytes are folded, the continuation starts with a space.
+pub(crate) fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+fn date_time(date: NaiveDate) -> String {
+    format!("{}T000000Z", date.format("%Y%m%d"))
+}
+
+/// A UID that stays the same across exports, from the `id:` tag or the task content.
+fn uid(task: &Task) -> String {
+    if let Some(id) = task.tags.get("id") {
+        return format!("{id}@todors");
+    }
+
+    // FNV-1a, unlike the std hasher its output is guaranteed to never change
+    let content = format!("{:?} {}", task.created_at, task.subject);
+    let hash = content.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}@todors")
+}
+
+/// The lines of the VTODO of `task`, unfolded.
+pub(crate) fn vtodo(task: &Task, now: DateTime<Utc>) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", uid(task)),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("SUMMARY:{}", escape(&task.subject)),
+    ];
+
+    if let Some(created_at) = task.created_at {
+        lines.push(format!("CREATED:{}", date_time(created_at)));
+    }
+    // A due time is in local time, written as a floating date-time
+    if let Some(due_at) = task.due_at() {
+        lines.push(format!("DUE:{}", due_at.format("%Y%m%dT%H%M%S")));
+    } else if let Some(due_date) = task.due_date {
+        lines.push(format!("DUE;VALUE=DATE:{}", due_date.format("%Y%m%d")));
+    }
+    if let Some(priority) = task.priority {
+        let priority = (priority as u8 - b'A' + 1).min(9);
+        lines.push(format!("PRIORITY:{priority}"));
+    }
+
+    let categories: Vec<String> = task
+        .projects
+        .iter()
+        .chain(&task.contexts)
+        .map(|category| escape(category))
+        .collect();
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+
+    if task.completed {
+        lines.push("STATUS:COMPLETED".to_string());
+        if let Some(completed_at) = task.completed_at {
+            lines.push(format!("COMPLETED:{}", date_time(completed_at)));
+        }
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+
+    lines.push("END:VTODO".to_string());
+
+    lines
+}
+
+/// An iCalendar file with a VTODO per task. Priorities A to I are mapped to 1 to 9 and the
+/// projects and contexts are used as categories.
+pub fn to_ics(tasks: &[Task], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//todors//todors//EN".to_string(),
+    ];
+
+    for task in tasks {
+        lines.extend(vtodo(task, now));
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{fold, to_ics};
+    use crate::tasks::task::Task;
+
+    #[test]
+    fn it_exports_vtodos() {
+        let tasks = [
+            Task::from_str(
+                1,
+                "(B) 2024-06-01 call mom, then dad @phone +family due:2024-06-10 id:42",
+            )
+            .unwrap(),
+            Task::from_str(2, "x 2024-06-02 2024-06-01 write report").unwrap(),
+        ];
+        let now = Utc.with_ymd_and_hms(2024, 6, 3, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            to_ics(&tasks, now).replace("\r\n", "\n"),
+            "BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//todors//todors//EN
+BEGIN:VTODO
+UID:42@todors
+DTSTAMP:20240603T120000Z
+SUMMARY:call mom\\, then dad @phone +family
+CREATED:20240601T000000Z
+DUE;VALUE=DATE:20240610
+PRIORITY:2
+CATEGORIES:family,phone
+STATUS:NEEDS-ACTION
+END:VTODO
+BEGIN:VTODO
+UID:67c6a78e36f13cb2@todors
+DTSTAMP:20240603T120000Z
+SUMMARY:write report
+CREATED:20240601T000000Z
+STATUS:COMPLETED
+COMPLETED:20240602T000000Z
+END:VTODO
+END:VCALENDAR
+"
+        );
+    }
+
+    #[test]
+    fn it_folds_long_lines() {
+        let folded = fold(&format!("SUMMARY:{}", "a".repeat(100)));
+
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(
+            folded.replace("\r\n ", ""),
+            format!("SUMMARY:{}\r\n", "a".repeat(100))
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use chrono::NaiveDate;
+
+use super::{Imported, group_by_project};
+use crate::tasks::{error::TaskError, list::TaskList, task::Task};
+
+/// Emojis of the Obsidian Tasks plugin, the date that follows them is kept.
+const DUE: &str = "📅";
+const CREATED: &str = "➕";
+const DONE: &str = "✅";
+const PRIORITIES: [(&str, char); 4] = [("🔺", 'A'), ("⏫", 'A'), ("🔼", 'B'), ("🔽", 'C')];
+
+/// Remove `marker` and the date after it from `text`.
+fn take_date(text: &mut String, marker: &str) -> Option<NaiveDate> {
+    let start = text.find(marker)?;
+    let rest = text[start + marker.len()..].trim_start();
+    let date = rest
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+
+    let end = text.len() - rest.len() + if date.is_some() { 10 } else { 0 };
+    text.replace_range(start..end, "");
+
+    date
+}
+
+/// Remove the `(D)` marker written for the priorities without an emoji. Like in todo.txt, one
+/// at the start of the text is part of the description.
+fn take_priority(text: &mut String) -> Option<char> {
+    let (start, priority) = text.match_indices(" (").find_map(|(start, _)| {
+        let marker = text[start + 1..].split_whitespace().next()?;
+        let priority = marker.strip_prefix('(')?.strip_suffix(')')?;
+        let mut chars = priority.chars();
+        match (chars.next(), chars.next()) {
+            (Some(priority @ 'A'..='Z'), None) => Some((start, priority)),
+            _ => None,
+        }
+    })?;
+    text.replace_range(start..start + 4, "");
+
+    Some(priority)
+}
+
+/// The `- [ ]` and `- [x]` items of a Markdown checklist, other lines are ignored. With
+/// `headings_as_projects`, the last heading before an item is used as its project.
+pub fn from_markdown(content: &str, headings_as_projects: bool) -> Result<TaskList, TaskError> {
+    let mut tasks = TaskList::new();
+    let mut heading = None;
+
+    for line in content.lines() {
+        let line = line.trim_start();
+
+        if line.starts_with('#') {
+            if headings_as_projects {
+                heading = Some(line.trim_start_matches('#').trim().to_string());
+            }
+            continue;
+        }
+
+        let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| line.strip_prefix(bullet))
+        else {
+            continue;
+        };
+        let (completed, text) = if let Some(text) = item.strip_prefix("[ ] ") {
+            (false, text)
+        } else if let Some(text) = item
+            .strip_prefix("[x] ")
+            .or_else(|| item.strip_prefix("[X] "))
+        {
+            (true, text)
+        } else {
+            continue;
+        };
+
+        let mut text = text.to_string();
+        let due_date = take_date(&mut text, DUE);
+        let created_at = take_date(&mut text, CREATED);
+        let completed_at = take_date(&mut text, DONE);
+        let priority = PRIORITIES
+            .iter()
+            .find_map(|(emoji, priority)| {
+                text.contains(emoji).then(|| {
+                    text = text.replace(emoji, "");
+                    *priority
+                })
+            })
+            .or_else(|| take_priority(&mut text));
+
+        tasks.push(
+            Imported {
+                description: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                project: heading.clone(),
+                priority,
+                due_date,
+                created_at,
+                completed,
+                completed_at,
+                ..Imported::default()
+            }
+            .into_task()?,
+        );
+    }
+
+    Ok(tasks)
+}
+
+fn item(task: &Task) -> String {
+    let mut item = format!("- [{}] ", if task.completed { 'x' } else { ' ' });
+    item.push_str(&task.subject);
+
+    if let Some((emoji, _)) = PRIORITIES[1..]
+        .iter()
+        .find(|(_, priority)| task.priority == Some(*priority) && !task.completed)
+    {
+        item.push_str(&format!(" {emoji}"));
+    } else if let Some(priority) = task.priority.filter(|_| !task.completed) {
+        // There's no emoji past the low priority
+        item.push_str(&format!(" ({priority})"));
+    }
+    if let Some(due_date) = task.due_date {
+        item.push_str(&format!(" {DUE} {due_date}"));
+    }
+    if let Some(completed_at) = task.completed_at {
+        item.push_str(&format!(" {DONE} {completed_at}"));
+    }
+
+    item.push('\n');
+    item
+}
+
+/// A checklist that [`from_markdown`] can read back, with a section per project when
+/// `by_project` is set, read back with `headings_as_projects`.
+pub fn to_markdown(tasks: &[Task], by_project: bool) -> String {
+    if !by_project {
+        return tasks.iter().map(item).collect();
+    }
+
+    group_by_project(tasks)
+        .into_iter()
+        .map(|(project, tasks)| {
+            let items: String = tasks.into_iter().map(item).collect();
+            match project {
+                Some(project) => format!("## {project}\n\n{items}\n"),
+                None => format!("{items}\n"),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_markdown, to_markdown};
+    use crate::tasks::task::Task;
+
+    #[test]
+    fn it_imports_checklist_items() {
+        let content = "Some notes
+
+- [ ] call mom @phone
+## Release 2.0
+- [x] write notes ✅ 2024-06-02
+  * [ ] publish ⏫ 📅 2024-06-10 #blog
+- not a task
+";
+
+        let lines: Vec<String> = from_markdown(content, true)
+            .unwrap()
+            .iter()
+            .map(|task| task.to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "call mom @phone",
+                "x 2024-06-02 write notes +Release-2.0",
+                "(A) publish #blog +Release-2.0 due:2024-06-10",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_keeps_the_priorities_without_an_emoji() {
+        let tasks = vec![
+            Task::from_str(0, "(D) water plants").unwrap(),
+            Task::from_str(0, "(B) call mom").unwrap(),
+        ];
+
+        let markdown = to_markdown(&tasks, false);
+
+        assert_eq!(markdown, "- [ ] water plants (D)\n- [ ] call mom 🔼\n");
+        assert_eq!(from_markdown(&markdown, false).unwrap(), tasks);
+    }
+
+    #[test]
+    fn it_ignores_headings_unless_asked_to() {
+        let tasks = from_markdown("# Shopping list\n- [ ] buy milk\n", false).unwrap();
+
+        assert_eq!(tasks[0].to_string(), "buy milk");
+    }
+
+    #[test]
+    fn it_keeps_todo_txt_markers_in_the_description() {
+        let tasks = from_markdown(
+            "- [ ] x marks the spot\n- [ ] (A) team sync\n- [ ] 2024-06-01 budget\n",
+            false,
+        )
+        .unwrap();
+
+        let subjects: Vec<&str> = tasks.iter().map(|task| task.subject.as_str()).collect();
+        assert_eq!(
+            subjects,
+            ["x marks the spot", "(A) team sync", "2024-06-01 budget"]
+        );
+        assert!(
+            tasks.iter().all(|task| !task.completed
+                && task.priority.is_none()
+                && task.created_at.is_none())
+        );
+    }
+
+    #[test]
+    fn it_exports_checklists_grouped_by_project() {
+        let tasks = from_markdown(
+            "- [ ] call mom\n- [ ] publish ⏫ 📅 2024-06-10 +blog\n- [x] write notes +blog ✅ 2024-06-02\n",
+            false,
+        )
+        .unwrap();
+
+        let markdown = to_markdown(&tasks, true);
+
+        assert_eq!(
+            markdown,
+            "- [ ] call mom
+
+## blog
+
+- [ ] publish +blog ⏫ 📅 2024-06-10
+- [x] write notes +blog ✅ 2024-06-02
+
+"
+        );
+        assert_eq!(from_markdown(&markdown, true).unwrap(), tasks);
+    }
+}
@@ -0,0 +1,90 @@
+//! Conversions between todo.txt tasks and the formats of other tools.
+
+mod html;
+mod ics;
+mod markdown;
+mod taskwarrior;
+mod todoist;
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::tasks::{error::TaskError, task::Task};
+
+pub use html::to_html;
+pub use ics::to_ics;
+pub(crate) use ics::{escape as escape_ics, fold as fold_ics, vtodo};
+pub use markdown::{from_markdown, to_markdown};
+pub use taskwarrior::from_taskwarrior_json;
+pub use todoist::from_todoist_csv;
+
+/// The fields found in other formats, turned into a task with [`Imported::into_task`].
+#[derive(Debug, Default)]
+struct Imported {
+    description: String,
+    project: Option<String>,
+    hashtags: Vec<String>,
+    priority: Option<char>,
+    due_date: Option<NaiveDate>,
+    created_at: Option<NaiveDate>,
+    completed: bool,
+    completed_at: Option<NaiveDate>,
+}
+
+impl Imported {
+    /// The description is parsed like a todo.txt subject so the contexts, projects and tags it
+    /// already contains are kept, and not added twice. The completion, priority and dates only
+    /// come from the fields of the format, never from the description.
+    fn into_task(self) -> Result<Task, TaskError> {
+        let mut task = Task::from_subject(0, self.description.trim())?;
+
+        let mut subject = task.subject.clone();
+        if let Some(project) = self.project.map(|project| slug(&project))
+            && !task.projects.contains(&project)
+        {
+            subject.push_str(&format!(" +{project}"));
+        }
+        for hashtag in self.hashtags.iter().map(|hashtag| slug(hashtag)) {
+            if !task.hashtags.contains(&hashtag) {
+                subject.push_str(&format!(" #{hashtag}"));
+            }
+        }
+
+        let parsed = Task::from_subject(0, &subject)?;
+        task.subject = parsed.subject;
+        task.projects = parsed.projects;
+        task.hashtags = parsed.hashtags;
+        task.priority = self.priority.or(task.priority);
+        task.due_date = self.due_date.or(task.due_date);
+        task.created_at = self.created_at.or(task.created_at);
+        if self.completed {
+            task.completed = true;
+            task.completed_at = self.completed_at;
+            task.priority = None;
+        }
+
+        Ok(task)
+    }
+}
+
+/// The tasks without a project first, then the tasks of each project by name. Tasks with several
+/// projects are listed under the first one.
+fn group_by_project(tasks: &[Task]) -> Vec<(Option<&String>, Vec<&Task>)> {
+    let mut groups: BTreeMap<Option<&String>, Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        groups.entry(task.projects.first()).or_default().push(task);
+    }
+
+    groups.into_iter().collect()
+}
+
+/// Projects and tags can't contain spaces in todo.txt.
+fn slug(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+fn invalid(format: &str, err: impl std::fmt::Display) -> TaskError {
+    eprintln!("Invalid {format} file: {err}");
+    TaskError::FailedToImport
+}
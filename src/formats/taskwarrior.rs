@@ -0,0 +1,87 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use super::{Imported, invalid};
+use crate::tasks::{error::TaskError, list::TaskList};
+
+/// A task of `task export`, only the fields todo.txt can represent.
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    description: String,
+    status: String,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    priority: Option<String>,
+    due: Option<String>,
+    entry: Option<String>,
+    end: Option<String>,
+}
+
+/// Dates are exported as `20240601T120000Z`.
+fn date(value: Option<&String>) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value?.get(..8)?, "%Y%m%d").ok()
+}
+
+/// The JSON array printed by `task export`. Deleted tasks and recurring templates are skipped,
+/// the H, M and L priorities become A, B and C and the tags become hashtags.
+pub fn from_taskwarrior_json(content: &str) -> Result<TaskList, TaskError> {
+    let exported: Vec<TaskwarriorTask> =
+        serde_json::from_str(content).map_err(|err| invalid("taskwarrior", err))?;
+
+    exported
+        .into_iter()
+        .filter(|task| !matches!(task.status.as_str(), "deleted" | "recurring"))
+        .map(|task| {
+            Imported {
+                priority: match task.priority.as_deref() {
+                    Some("H") => Some('A'),
+                    Some("M") => Some('B'),
+                    Some("L") => Some('C'),
+                    _ => None,
+                },
+                due_date: date(task.due.as_ref()),
+                created_at: date(task.entry.as_ref()),
+                completed: task.status == "completed",
+                completed_at: date(task.end.as_ref()),
+                description: task.description,
+                project: task.project,
+                hashtags: task.tags,
+            }
+            .into_task()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_taskwarrior_json;
+
+    #[test]
+    fn it_imports_a_task_export() {
+        let content = r#"[
+            {"id":1,"description":"write report","status":"pending","project":"work","tags":["next","office"],"priority":"H","due":"20240610T220000Z","entry":"20240601T080000Z","uuid":"a"},
+            {"id":0,"description":"call mom","status":"completed","priority":"M","entry":"20240601T080000Z","end":"20240602T100000Z","uuid":"b"},
+            {"id":0,"description":"old idea","status":"deleted","uuid":"c"}
+        ]"#;
+
+        let lines: Vec<String> = from_taskwarrior_json(content)
+            .unwrap()
+            .iter()
+            .map(|task| task.to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "(A) 2024-06-01 write report +work #next #office due:2024-06-10",
+                "x 2024-06-02 2024-06-01 call mom",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_json() {
+        assert!(from_taskwarrior_json("{").is_err());
+    }
+}
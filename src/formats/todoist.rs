@@ -0,0 +1,87 @@
+use chrono::NaiveDate;
+
+use super::{Imported, invalid};
+use crate::tasks::{error::TaskError, list::TaskList};
+
+/// The CSV export of a Todoist project. Sections become projects, `@labels` are already contexts
+/// in the content, priorities 1 to 3 become A to C and only `YYYY-MM-DD` dates are kept since the
+/// others are written in natural language.
+pub fn from_todoist_csv(content: &str) -> Result<TaskList, TaskError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|err| invalid("todoist", err))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+    let (Some(kind), Some(text)) = (column("TYPE"), column("CONTENT")) else {
+        return Err(invalid("todoist", "missing the TYPE or CONTENT column"));
+    };
+    let priority = column("PRIORITY");
+    let date = column("DATE");
+
+    let mut tasks = TaskList::new();
+    let mut section = None;
+    for record in reader.records() {
+        let record = record.map_err(|err| invalid("todoist", err))?;
+        let field = |idx: Option<usize>| idx.and_then(|idx| record.get(idx)).unwrap_or("");
+
+        match field(Some(kind)) {
+            "section" => section = Some(field(Some(text)).to_string()),
+            "task" => tasks.push(
+                Imported {
+                    description: field(Some(text)).to_string(),
+                    project: section.clone(),
+                    priority: match field(priority) {
+                        "1" => Some('A'),
+                        "2" => Some('B'),
+                        "3" => Some('C'),
+                        _ => None,
+                    },
+                    due_date: field(date)
+                        .get(..10)
+                        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+                    ..Imported::default()
+                }
+                .into_task()?,
+            ),
+            _ => {}
+        }
+    }
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_todoist_csv;
+
+    #[test]
+    fn it_imports_a_project_export() {
+        let content =
+            "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE
+task,Buy milk @store,,4,1,Me,,,en,UTC
+,,,,,,,,,
+section,Release 2.0,,,,,,,,
+task,\"Write notes, then publish\",,1,1,Me,,2024-06-10,en,UTC
+task,Water plants,,2,1,Me,,every day,en,UTC
+";
+
+        let lines: Vec<String> = from_todoist_csv(content)
+            .unwrap()
+            .iter()
+            .map(|task| task.to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Buy milk @store",
+                "(A) Write notes, then publish +Release-2.0 due:2024-06-10",
+                "(B) Water plants +Release-2.0",
+            ]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use crate::{
+    config::{Config, StorageBackend, SyncStrategy},
+    tasks::{error::TaskError, task::Task},
+};
+
+/// The todo dir as a git repository: the todo files are committed after each change and synced
+/// with a remote by `todors sync`.
+pub struct GitHistory {
+    todo_dir: PathBuf,
+    files: Vec<PathBuf>,
+    auto_commit: bool,
+    remote: String,
+    strategy: SyncStrategy,
+}
+
+impl GitHistory {
+    pub fn new(config: &Config) -> Self {
+        let files = match config.backend {
+            StorageBackend::TodoTxt => vec![config.todo_file(), config.done_file()],
+            StorageBackend::Sqlite => vec![config.database_file()],
+        };
+
+        Self {
+            todo_dir: config.todo_dir(),
+            files,
+            auto_commit: config.git.auto_commit,
+            remote: config.git.remote.clone(),
+            strategy: config.git.sync,
+        }
+    }
+
+    /// Commit the changes of a command when `auto_commit` is enabled. Failures are only reported,
+    /// the tasks are already saved at this point.
+    pub fn record(&self, action: &str, tasks: &[Task]) {
+        if !self.auto_commit {
+            return;
+        }
+
+        if let Err(err) = self.commit(&commit_message(action, tasks)) {
+            eprintln!("Failed to commit the changes: {err}");
+        }
+    }
+
+    /// Commit the todo files if they changed, the repository is created if needed. Returns
+    /// whether a commit was made.
+    pub fn commit(&self, message: &str) -> Result<bool, TaskError> {
+        if std::fs::create_dir_all(&self.todo_dir).is_err() {
+            return Err(TaskError::FailedToOpenTodoFile);
+        }
+
+        if !self.is_repository()? {
+            self.git(["init", "--quiet"])?;
+        }
+
+        let files: Vec<&OsStr> = self
+            .files
+            .iter()
+            .filter(|file| file.exists())
+            .map(|file| file.as_os_str())
+            .collect();
+        if files.is_empty() {
+            return Ok(false);
+        }
+
+        self.git(
+            [OsStr::new("add"), OsStr::new("--")]
+                .into_iter()
+                .chain(files.iter().copied()),
+        )?;
+
+        let staged = ["diff", "--cached", "--quiet", "--"].map(OsStr::new);
+        if self
+            .run(staged.into_iter().chain(files.iter().copied()))?
+            .status
+            .success()
+        {
+            return Ok(false);
+        }
+
+        let commit = ["commit", "--quiet", "-m", message, "--"].map(OsStr::new);
+        self.git(commit.into_iter().chain(files.iter().copied()))?;
+
+        Ok(true)
+    }
+
+    /// Whether the todo dir is the root of a git repository. One only inside another repository,
+    /// like a dotfiles repository in the home dir, gets its own so the todo files aren't
+    /// committed there.
+    fn is_repository(&self) -> Result<bool, TaskError> {
+        let output = self.run(["rev-parse", "--show-toplevel"])?;
+        if !output.status.success() {
+            return Ok(false);
+        }
+
+        let toplevel = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        Ok(
+            match (toplevel.canonicalize(), self.todo_dir.canonicalize()) {
+                (Ok(toplevel), Ok(todo_dir)) => toplevel == todo_dir,
+                _ => false,
+            },
+        )
+    }
+
+    /// Commit the local changes, integrate the remote ones and push the result.
+    pub fn sync(&self) -> Result<(), TaskError> {
+        self.commit("sync: local changes")?;
+
+        let branch = self.git(["symbolic-ref", "--short", "HEAD"])?;
+        let remote_branch = self
+            .run(["ls-remote", "--exit-code", "--heads", &self.remote, &branch])?
+            .status
+            .success();
+
+        if remote_branch {
+            let strategy = match self.strategy {
+                SyncStrategy::Rebase => "--rebase",
+                SyncStrategy::Merge => "--no-rebase",
+            };
+
+            if let Err(err) = self.git(["pull", "--quiet", strategy, &self.remote, &branch]) {
+                let conflicts = self.git(["diff", "--name-only", "--diff-filter=U"])?;
+                if conflicts.is_empty() {
+                    return Err(err);
+                }
+
+                // Leave the repository as it was before the pull
+                let abort = match self.strategy {
+                    SyncStrategy::Rebase => ["rebase", "--abort"],
+                    SyncStrategy::Merge => ["merge", "--abort"],
+                };
+                self.git(abort)?;
+
+                return Err(TaskError::SyncConflict);
+            }
+        }
+
+        // Nothing to push before the first commit
+        if !self
+            .run(["rev-parse", "--verify", "--quiet", "HEAD"])?
+            .status
+            .success()
+        {
+            return Ok(());
+        }
+
+        self.git([
+            "push",
+            "--quiet",
+            &self.remote,
+            &format!("HEAD:refs/heads/{branch}"),
+        ])?;
+
+        Ok(())
+    }
+
+    fn run<I, S>(&self, args: I) -> Result<Output, TaskError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        Command::new("git")
+            .args(args)
+            .current_dir(&self.todo_dir)
+            .output()
+            .map_err(|err| {
+                eprintln!("Failed to run git: {err}");
+                TaskError::FailedToRunGit
+            })
+    }
+
+    /// Run git and return its trimmed output, failing when it exits with an error.
+    fn git<I, S>(&self, args: I) -> Result<String, TaskError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let output = self.run(args)?;
+        if !output.status.success() {
+            eprintln!("{}", String::from_utf8_lossy(&output.stderr).trim());
+            return Err(TaskError::FailedToRunGit);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// A commit message like `done: 3 tasks (+release)`.
+pub fn commit_message(action: &str, tasks: &[Task]) -> String {
+    if tasks.is_empty() {
+        return action.to_string();
+    }
+
+    let mut message = format!(
+        "{action}: {} task{}",
+        tasks.len(),
+        if tasks.len() == 1 { "" } else { "s" }
+    );
+
+    let mut projects: Vec<&String> = tasks.iter().flat_map(|task| &task.projects).collect();
+    projects.sort();
+    projects.dedup();
+    if !projects.is_empty() {
+        let projects: Vec<String> = projects
+            .iter()
+            .map(|project| format!("+{project}"))
+            .collect();
+        message.push_str(&format!(" ({})", projects.join(" ")));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::{GitHistory, commit_message};
+    use crate::config::Config;
+    use crate::tasks::task::Task;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}");
+
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// A todo dir cloned from `remote`, with auto commit enabled.
+    fn clone(remote: &Path, dir: &Path) -> GitHistory {
+        git(
+            dir.parent().unwrap(),
+            &[
+                "clone",
+                "--quiet",
+                remote.to_str().unwrap(),
+                dir.to_str().unwrap(),
+            ],
+        );
+        git(dir, &["config", "user.name", "todors"]);
+        git(dir, &["config", "user.email", "todors@example.com"]);
+
+        let mut config = Config {
+            todo_dir: dir.to_path_buf(),
+            ..Config::default()
+        };
+        config.git.auto_commit = true;
+
+        GitHistory::new(&config)
+    }
+
+    #[test]
+    fn it_describes_the_changes() {
+        let tasks = [
+            Task::from_str(1, "ship it +release").unwrap(),
+            Task::from_str(2, "write notes +release +docs").unwrap(),
+            Task::from_str(3, "call mom").unwrap(),
+        ];
+
+        assert_eq!(
+            commit_message("done", &tasks),
+            "done: 3 tasks (+docs +release)"
+        );
+        assert_eq!(commit_message("add", &tasks[2..]), "add: 1 task");
+        assert_eq!(commit_message("edit", &[]), "edit");
+    }
+
+    #[test]
+    fn it_creates_a_repository_in_a_todo_dir_inside_another_one() {
+        let root = tempfile::tempdir().unwrap();
+        git(root.path(), &["init", "--quiet"]);
+        let todo_dir = root.path().join("todo");
+        std::fs::create_dir(&todo_dir).unwrap();
+        std::fs::write(todo_dir.join("todo.txt"), "first\n").unwrap();
+
+        let config = Config {
+            todo_dir: todo_dir.clone(),
+            ..Config::default()
+        };
+
+        // Committing needs a git identity, only where it happens matters here
+        let _ = GitHistory::new(&config).commit("add");
+
+        assert!(todo_dir.join(".git").is_dir());
+        assert_eq!(git(root.path(), &["status", "--porcelain"]), "?? todo/");
+    }
+
+    #[test]
+    fn it_syncs_through_a_remote() {
+        let root = tempfile::tempdir().unwrap();
+        let remote = root.path().join("remote.git");
+        std::fs::create_dir(&remote).unwrap();
+        git(&remote, &["init", "--quiet", "--bare"]);
+
+        let laptop_dir = root.path().join("laptop");
+        let laptop = clone(&remote, &laptop_dir);
+        std::fs::write(laptop_dir.join("todo.txt"), "first +sync\n").unwrap();
+        laptop.record("add", &[Task::from_str(1, "first +sync").unwrap()]);
+        laptop.sync().unwrap();
+        assert_eq!(
+            git(&laptop_dir, &["log", "-1", "--format=%s"]),
+            "add: 1 task (+sync)"
+        );
+
+        let desktop_dir = root.path().join("desktop");
+        let desktop = clone(&remote, &desktop_dir);
+        std::fs::write(desktop_dir.join("done.txt"), "x first +sync\n").unwrap();
+        desktop.sync().unwrap();
+
+        std::fs::write(laptop_dir.join("todo.txt"), "first +sync\nsecond\n").unwrap();
+        laptop.record("add", &[Task::from_str(2, "second").unwrap()]);
+        laptop.sync().unwrap();
+
+        assert!(laptop_dir.join("done.txt").exists());
+        desktop.sync().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(desktop_dir.join("todo.txt")).unwrap(),
+            "first +sync\nsecond\n"
+        );
+    }
+}
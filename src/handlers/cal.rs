@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::{
+    calendar::{agenda, month_days, month_grid},
+    cli::Cal,
+    repository::TodoRepository,
+    tasks::error::TaskError,
+    utils::{TaskGroup, print_tasks_groups},
+};
+
+use super::report::with_archive;
+
+pub fn handle_cal(params: Cal, repo: TodoRepository, done_file: PathBuf) -> Result<(), TaskError> {
+    let today = Local::now().date_naive();
+
+    if let Some(days) = params.agenda {
+        // The agenda leaves the completed tasks out
+        let tasks = repo.all()?;
+        let groups: Vec<TaskGroup> = agenda(&tasks, days, today)
+            .into_iter()
+            .map(|(header, tasks)| (Some(header), tasks.into_iter().map(|t| (0, t)).collect()))
+            .collect();
+
+        return print_tasks_groups(&groups, tasks.len());
+    }
+
+    // The completed tasks moved to the archive are only needed to count them
+    let tasks = if params.completed {
+        with_archive(&repo, done_file)?
+    } else {
+        repo.query(None, false)?
+    };
+
+    let month = params.month.unwrap_or(today);
+    for line in month_grid(&month_days(&tasks, month), month, today, params.completed) {
+        println!("{line}");
+    }
+
+    Ok(())
+}
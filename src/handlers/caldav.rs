@@ -0,0 +1,27 @@
+use crate::{
+    caldav::{CalDavClient, sync},
+    cli::Caldav,
+    config::Config,
+    git::GitHistory,
+    hooks::Hooks,
+    storage::TaskStorage,
+    tasks::error::TaskError,
+};
+
+pub fn handle_caldav(_params: Caldav, config: Config) -> Result<(), TaskError> {
+    let client = CalDavClient::new(&config.caldav)?;
+    let storage = TaskStorage::from_config(&config)?.with_hooks(Hooks::new(&config));
+
+    let report = sync(&storage, &client, &config.caldav_state_file())?;
+    GitHistory::new(&config).record("caldav", &[]);
+
+    println!(
+        "Pushed {} tasks, pulled {}, deleted {}",
+        report.pushed, report.pulled, report.deleted
+    );
+    if report.conflicts > 0 {
+        return Err(TaskError::MergeConflicts(report.conflicts));
+    }
+
+    Ok(())
+}
@@ -1,9 +1,7 @@
-use crate::{cli::Clean, storage::TaskStorage, tasks::error::TaskError};
+use crate::{cli::Clean, repository::TodoRepository, tasks::error::TaskError};
 
-pub fn handle_clean(_params: Clean, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
+pub fn handle_clean(_params: Clean, repo: TodoRepository) -> Result<(), TaskError> {
+    repo.clean()?;
 
-    tasks.retain(|t| !t.completed);
-
-    storage.persist(tasks)
+    Ok(())
 }
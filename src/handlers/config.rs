@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use crate::{
+    cli::{ConfigAction, ConfigArgs},
+    config::{Config, error::ConfigError},
+};
+
+pub fn handle_config(
+    params: ConfigArgs,
+    config_path: &Path,
+    overrides: &[(String, String)],
+) -> Result<(), ConfigError> {
+    match params.action {
+        ConfigAction::Init(init) => {
+            if config_path.exists() && !init.force {
+                return Err(ConfigError::AlreadyExists(config_path.to_path_buf()));
+            }
+
+            let mut config = Config::default();
+            if let Some(todo_dir) = init.todo_dir {
+                config.todo_dir = todo_dir;
+            }
+
+            write_file(config_path, &config.to_toml()?)?;
+            if !config.todo_file().exists() {
+                write_file(&config.todo_file(), "")?;
+            }
+
+            println!("Created {}", config_path.display());
+            println!("Tasks are stored in {}", config.todo_file().display());
+        }
+        ConfigAction::Show(_) => {
+            let config = Config::load(config_path, overrides)?;
+
+            if !config_path.exists() {
+                println!("# {} not found, using the defaults", config_path.display());
+            }
+            println!("# todo file: {}", config.todo_file().display());
+            print!("{}", config.to_toml()?);
+        }
+        ConfigAction::Path(_) => println!("{}", config_path.display()),
+    }
+
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| ConfigError::FailedToWrite(parent.to_path_buf(), err))?;
+    }
+
+    std::fs::write(path, content).map_err(|err| ConfigError::FailedToWrite(path.to_path_buf(), err))
+}
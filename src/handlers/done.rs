@@ -1,25 +1,30 @@
 use crate::cli::Done;
-use crate::tasks::list::{TaskList, TaskListTrait};
 
 use crate::{
-    storage::TaskStorage,
-    tasks::{error::TaskError, query::TaskQuery},
+    repository::TodoRepository,
+    tasks::{dependencies::open_children, error::TaskError, query::TaskQuery},
 };
 
 use crate::utils::print_tasks_list;
 
-pub fn handle_done(params: Done, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
-    let total = tasks.len();
+pub fn handle_done(params: Done, repo: TodoRepository) -> Result<(), TaskError> {
     let query = TaskQuery::from_string_vec(&params.query)?;
 
-    tasks
-        .filter_mut_from_query(&query)
-        .for_each(|task| task.complete());
+    let completed_tasks = repo.complete(&query)?;
 
-    let completed_tasks: TaskList = tasks.filter_from_query(&query).collect();
+    let tasks = repo.all()?;
+    for task in &completed_tasks {
+        let open: Vec<String> = open_children(&tasks, task)
+            .map(|child| child.id.to_string())
+            .collect();
+        if !open.is_empty() {
+            eprintln!(
+                "Warning: task {} still has open subtasks: {}",
+                task.id,
+                open.join(", ")
+            );
+        }
+    }
 
-    print_tasks_list(&completed_tasks, total)?;
-
-    storage.persist(tasks)
+    print_tasks_list(&completed_tasks, tasks.len())
 }
@@ -1,21 +1,24 @@
 use crate::cli::Due;
-use crate::{storage::TaskStorage, tasks::error::TaskError};
+use crate::{repository::TodoRepository, tasks::error::TaskError};
 
 use crate::utils::print_tasks_list;
 
+use super::watch::redraw_on_change;
+
 // TODO: a query or an argument to list tasks due today, tomorrow, this week, next week, this
 // month, next month
 // For now we'll just list all due tasks by date
-pub fn handle_due(_params: Due, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
-    let total = tasks.len();
-
-    // TODO: is there a way to have a less leaky interface for this?
-    // It'd probably not be the job of the list to know about due stuff.
-    tasks.retain(|task| !task.completed && task.due_date.is_some());
-    tasks.sort_by_key(|task| task.due_date);
+pub fn handle_due(params: Due, repo: TodoRepository) -> Result<(), TaskError> {
+    redraw_on_change(&repo, params.watch, || {
+        let mut tasks = repo.all()?;
+        let total = tasks.len();
 
-    print_tasks_list(&tasks, total)?;
+        // TODO: is there a way to have a less leaky interface for this?
+        // It'd probably not be the job of the list to know about due stuff.
+        tasks.retain(|task| !task.completed && task.due_date.is_some());
+        // The tasks due on a date without a time come before the timed ones of that day
+        tasks.sort_by_key(|task| task.due_key());
 
-    Ok(())
+        print_tasks_list(&tasks, total)
+    })
 }
@@ -1,24 +1,179 @@
-use crate::{cli::Edit, config::Config, tasks::error::TaskError};
-
-pub fn handle_edit(params: Edit, config: Config) -> Result<(), TaskError> {
-    let editor = match std::env::var("EDITOR") {
-        Ok(value) => value,
-        // TODO: check if nvim -> vim -> nano is in the path, else bailout
-        // TODO: add (better) loggin / log that if you want to chose the editor, set the EDITOR env
-        // var
-        Err(_) => "nvim".to_string(),
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+use crate::{
+    cli::Edit,
+    config::{Config, StorageBackend},
+    git::GitHistory,
+    repository::TodoRepository,
+    tasks::{error::TaskError, list::TaskList, query::TaskQuery, task::Task},
+    utils::{find_executable, split_command},
+};
+
+const FALLBACK_EDITORS: [&str; 4] = ["nvim", "vim", "vi", "nano"];
+
+pub fn handle_edit(params: Edit, repo: TodoRepository, config: Config) -> Result<(), TaskError> {
+    let editor = resolve_editor(&config)?;
+
+    let Some(query) = params.query else {
+        // There is no file to open with the sqlite backend, only the query mode works
+        if config.backend != StorageBackend::TodoTxt {
+            return Err(TaskError::UnsupportedByBackend);
+        }
+
+        run_editor(&editor, &config.todo_file())?;
+        GitHistory::new(&config).record("edit", &[]);
+
+        return Ok(());
     };
-    let mut cmd = std::process::Command::new(editor);
 
-    if let Some(item) = params.item {
-        cmd.arg(format!("+{item}"));
+    let query = TaskQuery::from_string_vec(&query)?;
+    repo.edit(&query, |selected| edit_tasks(&editor, selected))?;
+
+    Ok(())
+}
+
+/// Let the user edit `selected` in a temporary file until every line is a valid task.
+fn edit_tasks(editor: &[String], selected: &TaskList) -> Result<TaskList, TaskError> {
+    let Ok(mut file) = tempfile::Builder::new()
+        .prefix("todors-")
+        .suffix(".txt")
+        .tempfile()
+    else {
+        return Err(TaskError::FailedToCreateTempFile);
+    };
+    for task in selected {
+        if writeln!(file, "{task}").is_err() {
+            return Err(TaskError::FailedToCreateTempFile);
+        }
     }
 
-    if let Err(e) = cmd.arg(config.todo_file()).status() {
-        // TODO: use a logging library instead of `eprintln!`
-        eprintln!("Failed to edit the todo file: {e}");
-        return Err(TaskError::FailedToOpenTodoFile);
+    loop {
+        run_editor(editor, file.path())?;
+
+        let Ok(content) = std::fs::read_to_string(file.path()) else {
+            return Err(TaskError::FailedToCreateTempFile);
+        };
+
+        match parse_edited(&content) {
+            Ok(edited) => return Ok(edited),
+            Err(errors) => {
+                for (line, err) in errors {
+                    eprintln!("Line {line}: {err}");
+                }
+
+                if !confirm("Edit again? [Y/n] ") {
+                    return Err(TaskError::EditAborted);
+                }
+            }
+        }
     }
+}
 
-    Ok(())
+/// Find the editor to use: `$VISUAL`, then `$EDITOR`, then the `editor` config key and finally
+/// the first well known editor available in the `PATH`.
+fn resolve_editor(config: &Config) -> Result<Vec<String>, TaskError> {
+    let configured = [
+        std::env::var("VISUAL").ok(),
+        std::env::var("EDITOR").ok(),
+        config.editor.clone(),
+    ];
+
+    if let Some(command) = configured
+        .into_iter()
+        .flatten()
+        .find(|command| !command.trim().is_empty())
+    {
+        return Ok(split_command(&command));
+    }
+
+    FALLBACK_EDITORS
+        .iter()
+        .find(|editor| find_executable(editor).is_some())
+        .map(|editor| vec![editor.to_string()])
+        .ok_or(TaskError::EditorNotFound)
+}
+
+fn run_editor(editor: &[String], path: &Path) -> Result<(), TaskError> {
+    let Some((program, args)) = editor.split_first() else {
+        return Err(TaskError::EditorNotFound);
+    };
+
+    // TODO: use a logging library instead of `eprintln!`
+    match Command::new(program).args(args).arg(path).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => {
+            eprintln!("The editor `{program}` exited with {status}");
+            Err(TaskError::EditorFailed)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            eprintln!("The editor `{program}` was not found");
+            Err(TaskError::EditorNotFound)
+        }
+        Err(e) => {
+            eprintln!("Failed to run the editor `{program}`: {e}");
+            Err(TaskError::EditorFailed)
+        }
+    }
+}
+
+fn confirm(prompt: &str) -> bool {
+    eprint!("{prompt}");
+
+    let mut answer = String::new();
+    // At the end of the input, e.g. when it isn't a terminal, there's nobody to say yes
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) | Err(_) => false,
+        Ok(_) => matches!(answer.trim(), "" | "y" | "Y" | "yes"),
+    }
+}
+
+/// Parse every non-blank line of the edited file, collecting all the errors with their line
+/// number so they can be reported at once.
+fn parse_edited(content: &str) -> Result<TaskList, Vec<(usize, TaskError)>> {
+    let mut tasks = TaskList::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match Task::from_str(0, line) {
+            Ok(task) if task.subject.is_empty() => errors.push((idx + 1, TaskError::FailedToParse)),
+            Ok(task) => tasks.push(task),
+            Err(err) => errors.push((idx + 1, err)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(tasks)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TaskList, parse_edited};
+
+    fn subjects(tasks: &TaskList) -> Vec<&str> {
+        tasks.iter().map(|task| task.subject.as_str()).collect()
+    }
+
+    #[test]
+    fn it_skips_blank_lines_when_parsing() {
+        let result = parse_edited("(A) one\n\n  \ntwo\n").unwrap();
+
+        assert_eq!(subjects(&result), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn it_reports_every_invalid_line() {
+        let errors = parse_edited("x 2024-05-01\nok\ndue:2024-06-01\n").unwrap_err();
+
+        let lines: Vec<usize> = errors.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 3]);
+    }
 }
@@ -0,0 +1,41 @@
+use std::io::Write;
+
+use chrono::Utc;
+
+use crate::{
+    cli::{Export, ExportFormat},
+    formats::{to_html, to_ics, to_markdown},
+    repository::TodoRepository,
+    storage::{Backend, SqliteBackend},
+    tasks::{error::TaskError, query::TaskQuery},
+};
+
+pub fn handle_export(params: Export, repo: TodoRepository) -> Result<(), TaskError> {
+    let query = params
+        .query
+        .map(|query| TaskQuery::from_string_vec(&query))
+        .transpose()?;
+
+    let tasks = repo.query(query.as_ref(), true)?;
+
+    let content = match params.to {
+        ExportFormat::TodoTxt => tasks.iter().map(|task| format!("{task}\n")).collect(),
+        ExportFormat::Ics => to_ics(&tasks, Utc::now()),
+        ExportFormat::Markdown => to_markdown(&tasks, params.by_project),
+        ExportFormat::Html => to_html(&tasks, params.by_project),
+        ExportFormat::Sqlite => {
+            let Some(output) = params.output else {
+                return Err(TaskError::OutputRequired);
+            };
+
+            return SqliteBackend::open(&output)?.persist(tasks);
+        }
+    };
+
+    match params.output {
+        Some(output) => std::fs::write(output, content).map_err(|_| TaskError::FailedToSave),
+        None => std::io::stdout()
+            .write_all(content.as_bytes())
+            .map_err(|_| TaskError::FailedToWriteToStdout),
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use crate::{
+    cli::{Import, ImportFormat},
+    formats::{from_markdown, from_taskwarrior_json, from_todoist_csv},
+    repository::TodoRepository,
+    storage::{Backend, SqliteBackend, TodoTxtBackend},
+    tasks::error::TaskError,
+};
+
+use crate::utils::print_tasks_list;
+
+fn read(path: &Path) -> Result<String, TaskError> {
+    std::fs::read_to_string(path).map_err(|_| TaskError::FileNotFound(path.to_path_buf()))
+}
+
+pub fn handle_import(params: Import, repo: TodoRepository) -> Result<(), TaskError> {
+    if !params.file.exists() {
+        return Err(TaskError::FileNotFound(params.file));
+    }
+
+    let tasks = match params.from {
+        ImportFormat::TodoTxt => TodoTxtBackend::new(params.file).get_all()?,
+        ImportFormat::Sqlite => SqliteBackend::open(&params.file)?.get_all()?,
+        ImportFormat::TaskwarriorJson => from_taskwarrior_json(&read(&params.file)?)?,
+        ImportFormat::TodoistCsv => from_todoist_csv(&read(&params.file)?)?,
+        ImportFormat::Markdown => from_markdown(&read(&params.file)?, params.headings_as_projects)?,
+    };
+
+    if params.dry_run {
+        for task in tasks {
+            println!("{task}");
+        }
+
+        return Ok(());
+    }
+
+    let imported = repo.import(tasks)?;
+
+    print_tasks_list(&imported, repo.count()?)
+}
@@ -1,26 +1,80 @@
+use std::collections::BTreeMap;
+
 use crate::{
-    cli::List,
-    storage::TaskStorage,
-    tasks::{error::TaskError, list::TaskListTrait, query::TaskQuery},
+    cli::{GroupBy, List},
+    repository::TodoRepository,
+    tasks::{
+        dependencies::{Dependencies, tree},
+        duration::parse_duration,
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        query::TaskQuery,
+        task::Task,
+    },
 };
 
-use crate::utils::print_tasks_list;
+use crate::utils::{TaskGroup, print_tasks_groups};
 
-pub fn handle_list(params: List, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
-    let total = tasks.len();
+use super::watch::redraw_on_change;
 
-    if !params.all {
-        tasks.retain(|task| !task.completed)
-    }
+pub fn handle_list(params: List, repo: TodoRepository) -> Result<(), TaskError> {
+    let query = params
+        .query
+        .map(|query| TaskQuery::from_string_vec(&query))
+        .transpose()?;
+    let capacity = params
+        .capacity
+        .map(|capacity| parse_duration(&capacity).ok_or(TaskError::InvalidDuration(capacity)))
+        .transpose()?;
+
+    redraw_on_change(&repo, params.watch, || {
+        let all = repo.all()?;
+        let total = all.len();
+        let dependencies = Dependencies::new(&all);
+
+        let mut tasks: TaskList = match &query {
+            Some(query) => all.filter_from_query(query).collect(),
+            None => all,
+        };
+        if !params.all {
+            tasks.retain(|task| !task.completed);
+        }
+        tasks = tasks.sort_by_urgency(&dependencies);
+
+        // Planning only makes sense with the tasks that can be done now
+        if let Some(capacity) = capacity {
+            tasks.retain(|task| !task.completed && !dependencies.is_blocked(task));
+            tasks = tasks.within_capacity(capacity);
+        }
+
+        let groups: Vec<(Option<String>, TaskList)> = match params.by {
+            None => vec![(None, tasks)],
+            Some(by) => group_by(tasks, by),
+        };
+        let groups: Vec<TaskGroup> = groups
+            .iter()
+            .map(|(header, tasks)| (header.clone(), tree(tasks)))
+            .collect();
+
+        print_tasks_groups(&groups, total)
+    })
+}
 
-    if let Some(query) = &params.query {
-        let query = TaskQuery::from_string_vec(query)?;
+/// The tasks by their first project or context, keeping their order. Tasks without one come
+/// first.
+fn group_by(tasks: TaskList, by: GroupBy) -> Vec<(Option<String>, TaskList)> {
+    let key = |task: &Task| match by {
+        GroupBy::Project => task.projects.first().map(|project| format!("+{project}")),
+        GroupBy::Context => task.contexts.first().map(|context| format!("@{context}")),
+    };
 
-        tasks = tasks.filter_from_query(&query).collect();
+    let mut groups: BTreeMap<Option<String>, TaskList> = BTreeMap::new();
+    for task in tasks {
+        groups.entry(key(&task)).or_default().push(task);
     }
 
-    tasks = tasks.sort_by_urgency();
-    print_tasks_list(&tasks, total)?;
-    Ok(())
+    groups
+        .into_iter()
+        .map(|(header, tasks)| (Some(header.unwrap_or_else(|| "(none)".to_string())), tasks))
+        .collect()
 }
@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use crate::{cli::Merge, merge::merge, tasks::error::TaskError};
+
+fn read(path: &Path) -> Result<String, TaskError> {
+    std::fs::read_to_string(path).map_err(|_| TaskError::FileNotFound(path.to_path_buf()))
+}
+
+pub fn handle_merge(params: Merge) -> Result<(), TaskError> {
+    let merged = merge(
+        &read(&params.base)?,
+        &read(&params.ours)?,
+        &read(&params.theirs)?,
+    )?;
+
+    if params.stdout {
+        print!("{merged}");
+    } else if std::fs::write(&params.ours, merged.to_string()).is_err() {
+        return Err(TaskError::FailedToSave);
+    }
+
+    match merged.conflicts() {
+        0 => Ok(()),
+        count => Err(TaskError::MergeConflicts(count)),
+    }
+}
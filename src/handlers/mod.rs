@@ -1,21 +1,50 @@
 mod add;
+mod cal;
+mod caldav;
 mod clean;
+mod config;
 mod done;
 mod due;
 mod edit;
+mod export;
+mod import;
 mod list;
+mod merge;
 mod modify;
 mod next;
+mod remind;
 mod remove;
+mod report;
+mod serve;
+mod start;
+mod stats;
+mod stop;
+mod sync;
+mod tui;
 mod undone;
+mod watch;
 
 pub use add::handle_add;
+pub use cal::handle_cal;
+pub use caldav::handle_caldav;
 pub use clean::handle_clean;
+pub use config::handle_config;
 pub use done::handle_done;
 pub use due::handle_due;
 pub use edit::handle_edit;
+pub use export::handle_export;
+pub use import::handle_import;
 pub use list::handle_list;
+pub use merge::handle_merge;
 pub use modify::handle_modify;
 pub use next::handle_next;
+pub use remind::handle_remind;
 pub use remove::handle_remove;
+pub use report::handle_report;
+pub use serve::handle_serve;
+pub use start::handle_start;
+pub use stats::handle_stats;
+pub use stop::handle_stop;
+pub use sync::handle_sync;
+pub use tui::handle_tui;
 pub use undone::handle_undone;
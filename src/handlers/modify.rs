@@ -1,50 +1,48 @@
 use crate::{
     cli::Modify,
-    storage::TaskStorage,
-    tasks::{error::TaskError, list::TaskListTrait, query::TaskQuery},
+    repository::{TaskUpdate, TodoRepository},
+    tasks::{error::TaskError, query::TaskQuery},
 };
 
 // TODO: https://github.com/just1602/todors/issues/5
-pub fn handle_modify(params: Modify, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
+pub fn handle_modify(params: Modify, repo: TodoRepository) -> Result<(), TaskError> {
     let query = TaskQuery::from_string_vec(&params.query)?;
 
-    let idx_to_modify: Vec<usize> = tasks
-        .filter_from_query(&query)
-        .map(|task| task.id)
-        .collect();
+    let mut update = TaskUpdate::default();
 
     if params.priority.is_some() {
-        tasks.iter_mut().for_each(|task| {
-            if idx_to_modify.contains(&task.id) {
-                task.priority = params.priority
-            }
-        });
+        update.priority = Some(params.priority);
     }
 
     if params.rm_priority {
-        tasks.iter_mut().for_each(|task| {
-            if idx_to_modify.contains(&task.id) {
-                task.priority = None
-            }
-        });
+        update.priority = Some(None);
     }
 
     if params.due_date.is_some() {
-        tasks.iter_mut().for_each(|task| {
-            if idx_to_modify.contains(&task.id) {
-                task.due_date = params.due_date
-            }
-        });
+        update.due_date = Some(params.due_date);
     }
 
     if params.rm_due_date {
-        tasks.iter_mut().for_each(|task| {
-            if idx_to_modify.contains(&task.id) {
-                task.due_date = None
-            }
-        });
+        update.due_date = Some(None);
     }
 
-    storage.persist(tasks)
+    if params.parent.is_some() {
+        update.parent = Some(params.parent);
+    }
+
+    if params.rm_parent {
+        update.parent = Some(None);
+    }
+
+    if !params.dep.is_empty() {
+        update.dependencies = Some(params.dep);
+    }
+
+    if params.rm_dep {
+        update.dependencies = Some(Vec::new());
+    }
+
+    repo.modify(&query, &update)?;
+
+    Ok(())
 }
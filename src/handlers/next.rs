@@ -1,26 +1,43 @@
 use crate::{
     cli::Next,
-    storage::TaskStorage,
-    tasks::{error::TaskError, list::TaskListTrait, query::TaskQuery},
+    repository::TodoRepository,
+    tasks::{
+        dependencies::Dependencies,
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        query::TaskQuery,
+    },
 };
 
 use crate::utils::print_tasks_list;
 
-pub fn handle_next(params: Next, storage: TaskStorage) -> Result<(), TaskError> {
-    let mut tasks = storage.get_all()?;
-    let total = tasks.len();
+use super::watch::redraw_on_change;
 
-    if let Some(query) = &params.query {
-        let query = TaskQuery::from_string_vec(query)?;
+pub fn handle_next(params: Next, repo: TodoRepository) -> Result<(), TaskError> {
+    let query = params
+        .query
+        .map(|query| TaskQuery::from_string_vec(&query))
+        .transpose()?;
 
-        tasks = tasks.filter_from_query(&query).collect();
-    }
+    redraw_on_change(&repo, params.watch, || {
+        let all = repo.all()?;
+        let total = all.len();
+        let dependencies = Dependencies::new(&all);
 
-    if let Some(task) = tasks.sort_by_urgency().first() {
-        // FIXME: remove this clone
-        // TODO: check if this function can take a slice instead
-        print_tasks_list(&vec![task.clone()], total)?;
-    }
+        let mut tasks: TaskList = match &query {
+            Some(query) => all.filter_from_query(query).collect(),
+            None => all,
+        };
 
-    Ok(())
+        // A blocked task can't be the next one to do
+        tasks.retain(|task| !dependencies.is_blocked(task));
+
+        if let Some(task) = tasks.sort_by_urgency(&dependencies).first() {
+            // FIXME: remove this clone
+            // TODO: check if this function can take a slice instead
+            print_tasks_list(&vec![task.clone()], total)?;
+        }
+
+        Ok(())
+    })
 }
@@ -0,0 +1,52 @@
+use std::{collections::BTreeSet, path::PathBuf, time::Duration};
+
+use chrono::Local;
+
+use crate::{
+    cli::Remind,
+    config::RemindConfig,
+    remind::{notify, remind},
+    repository::TodoRepository,
+    tasks::error::TaskError,
+    utils::{load_json, save_json},
+};
+
+pub fn handle_remind(
+    params: Remind,
+    repo: TodoRepository,
+    config: RemindConfig,
+    state_file: PathBuf,
+) -> Result<(), TaskError> {
+    let check = || -> Result<usize, TaskError> {
+        let tasks = repo.query(None, false)?;
+        let mut notified: BTreeSet<String> = load_json(&state_file)?.unwrap_or_default();
+
+        let sent = remind(
+            &tasks,
+            Local::now().naive_local(),
+            &mut notified,
+            |reminder| notify(&config.command, reminder),
+        );
+        save_json(&state_file, &notified)?;
+
+        Ok(sent)
+    };
+
+    if !params.watch {
+        match check()? {
+            0 => println!("Nothing to remind"),
+            sent => println!("{sent} notifications sent"),
+        }
+        return Ok(());
+    }
+
+    let interval = Duration::from_secs(params.interval.unwrap_or(config.interval).max(1));
+    loop {
+        // A failed check, e.g. while the todo file is being edited, is retried on the next one
+        if let Err(err) = check() {
+            eprintln!("An error occured: {err}");
+        }
+
+        std::thread::sleep(interval);
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use chrono::{Local, TimeDelta};
+
+use crate::{
+    cli::{Report, ReportKind},
+    reports::{bar, burndown, time_report},
+    repository::TodoRepository,
+    storage::TaskStorage,
+    tasks::{duration::format_duration, error::TaskError, list::TaskList},
+};
+
+/// The tasks of the list and of the done archive, the completed tasks moved there still count.
+pub(super) fn with_archive(
+    repo: &TodoRepository,
+    done_file: PathBuf,
+) -> Result<TaskList, TaskError> {
+    let mut tasks = repo.all()?;
+    tasks.extend(TaskStorage::new(done_file).get_all()?);
+
+    Ok(tasks)
+}
+
+pub fn handle_report(
+    params: Report,
+    repo: TodoRepository,
+    done_file: PathBuf,
+) -> Result<(), TaskError> {
+    let tasks = with_archive(&repo, done_file)?;
+
+    match params.report {
+        ReportKind::Time(params) => {
+            let sheet = time_report(&tasks, params.by, params.since);
+            let width = sheet
+                .entries
+                .iter()
+                .map(|entry| entry.name.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            let estimated = |estimate: Option<TimeDelta>| {
+                estimate
+                    .map(|estimate| format!("{} estimated", format_duration(estimate)))
+                    .unwrap_or_default()
+            };
+
+            for entry in &sheet.entries {
+                println!(
+                    "{:width$}  {:>6}  {}",
+                    entry.name,
+                    format_duration(entry.spent),
+                    estimated(entry.estimate)
+                );
+            }
+            println!(
+                "⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯\n{:>width$}  {:>6}  {}",
+                "total",
+                format_duration(sheet.total),
+                estimated(sheet.estimate)
+            );
+        }
+        ReportKind::Burndown(params) => {
+            let days = burndown(&tasks, params.days, Local::now().date_naive());
+            let max = days.iter().map(|(_, open)| *open).max().unwrap_or(0);
+
+            for (day, open) in days {
+                println!("{day}  {open:>4}  {}", bar(open, max, 40));
+            }
+        }
+    }
+
+    Ok(())
+}
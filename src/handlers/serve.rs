@@ -0,0 +1,14 @@
+use crate::{
+    cli::Serve, config::ServerConfig, repository::TodoRepository, server::serve,
+    tasks::error::TaskError,
+};
+
+pub fn handle_serve(
+    params: Serve,
+    repo: TodoRepository,
+    config: ServerConfig,
+) -> Result<(), TaskError> {
+    let bind = params.bind.unwrap_or(config.bind);
+
+    serve(&bind, repo, config.token.as_deref())
+}
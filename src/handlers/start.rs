@@ -0,0 +1,29 @@
+use crate::{
+    cli::Start,
+    repository::TodoRepository,
+    tasks::error::TaskError,
+    timer::{Timer, TimerFile},
+};
+
+use super::stop::stop_timer;
+
+pub fn handle_start(
+    params: Start,
+    repo: TodoRepository,
+    timer_file: TimerFile,
+) -> Result<(), TaskError> {
+    // The task is looked up first, an unknown one leaves the running timer alone
+    let task = repo.identify(params.task)?;
+    let Some(stable_id) = task.stable_id() else {
+        return Err(TaskError::TaskNotFound);
+    };
+
+    // A single timer runs at a time, switching tasks stops the current one
+    if let Some(timer) = timer_file.load()? {
+        stop_timer(&repo, &timer_file, &timer)?;
+    }
+    timer_file.save(&Timer::new(stable_id.to_string()))?;
+
+    println!("Started {}) {}", task.id, task.subject);
+    Ok(())
+}
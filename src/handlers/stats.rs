@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::{
+    cli::{GroupBy, Stats},
+    reports::{
+        bar, breakdown, daily_activity, median_lead_time, overdue, sparkline, weekly_activity,
+    },
+    repository::TodoRepository,
+    tasks::error::TaskError,
+};
+
+use super::report::with_archive;
+
+pub fn handle_stats(
+    params: Stats,
+    repo: TodoRepository,
+    done_file: PathBuf,
+) -> Result<(), TaskError> {
+    let tasks = with_archive(&repo, done_file)?;
+    let today = Local::now().date_naive();
+
+    let open = tasks.iter().filter(|task| !task.completed).count();
+    println!(
+        "{open} open, {} completed, {} overdue",
+        tasks.len() - open,
+        overdue(&tasks, today)
+    );
+    match median_lead_time(&tasks) {
+        Some(days) => println!("Median lead time: {days:.1} days"),
+        None => println!("Median lead time: no completed task with both dates"),
+    }
+
+    let days = daily_activity(&tasks, params.days, today);
+    let created: Vec<usize> = days.iter().map(|day| day.created).collect();
+    let completed: Vec<usize> = days.iter().map(|day| day.completed).collect();
+    println!("\nLast {} days", params.days);
+    println!(
+        "  created    {}  {}",
+        sparkline(&created),
+        created.iter().sum::<usize>()
+    );
+    println!(
+        "  completed  {}  {}",
+        sparkline(&completed),
+        completed.iter().sum::<usize>()
+    );
+
+    let weeks = weekly_activity(&tasks, params.weeks, today);
+    let max = weeks
+        .iter()
+        .map(|week| week.created.max(week.completed))
+        .max()
+        .unwrap_or(0);
+    println!("\nWeek of      created  completed");
+    for week in weeks {
+        println!(
+            "{}  {:>7}  {:>9}  {}",
+            week.date,
+            week.created,
+            week.completed,
+            bar(week.completed, max, 20)
+        );
+    }
+
+    let groups = breakdown(&tasks, params.by, today);
+    let width = groups
+        .iter()
+        .map(|group| group.name.chars().count())
+        .chain([10])
+        .max()
+        .unwrap_or(0);
+    let max = groups.iter().map(|group| group.open).max().unwrap_or(0);
+    let header = match params.by {
+        GroupBy::Project => "By project",
+        GroupBy::Context => "By context",
+    };
+    println!("\n{header:width$}  open  completed  overdue");
+    for group in groups {
+        println!(
+            "{:width$}  {:>4}  {:>9}  {:>7}  {}",
+            group.name,
+            group.open,
+            group.completed,
+            group.overdue,
+            bar(group.open, max, 20)
+        );
+    }
+
+    Ok(())
+}
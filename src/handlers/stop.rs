@@ -0,0 +1,51 @@
+use chrono::Local;
+
+use crate::{
+    cli::Stop,
+    repository::TodoRepository,
+    tasks::{duration::format_duration, error::TaskError},
+    timer::{Timer, TimerFile},
+};
+
+pub fn handle_stop(
+    _params: Stop,
+    repo: TodoRepository,
+    timer_file: TimerFile,
+) -> Result<(), TaskError> {
+    let Some(timer) = timer_file.load()? else {
+        return Err(TaskError::NoRunningTimer);
+    };
+
+    stop_timer(&repo, &timer_file, &timer)
+}
+
+pub(super) fn stop_timer(
+    repo: &TodoRepository,
+    timer_file: &TimerFile,
+    timer: &Timer,
+) -> Result<(), TaskError> {
+    let elapsed = timer.elapsed(Local::now());
+    if elapsed.is_zero() {
+        println!("Stopped after less than a minute, no time was recorded");
+        return timer_file.clear();
+    }
+
+    // The timer is kept if the time couldn't be saved, unless its task is gone
+    let task = match repo.add_spent(&timer.task, elapsed) {
+        Err(TaskError::TaskNotFound) => {
+            timer_file.clear()?;
+            return Err(TaskError::TaskNotFound);
+        }
+        result => result?,
+    };
+    timer_file.clear()?;
+
+    println!(
+        "Stopped {}) {} after {}, {} spent in total",
+        task.id,
+        task.subject,
+        format_duration(elapsed),
+        format_duration(task.spent().unwrap_or_default())
+    );
+    Ok(())
+}
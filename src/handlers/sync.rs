@@ -0,0 +1,14 @@
+use crate::{
+    cli::Sync, config::Config, git::GitHistory, storage::TaskStorage, tasks::error::TaskError,
+};
+
+pub fn handle_sync(_params: Sync, config: Config) -> Result<(), TaskError> {
+    // No other command can change the files while they're pulled and rewritten
+    let storage = TaskStorage::from_config(&config)?;
+    let _lock = storage.lock()?;
+
+    GitHistory::new(&config).sync()?;
+
+    println!("Synced with {}", config.git.remote);
+    Ok(())
+}
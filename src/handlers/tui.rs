@@ -0,0 +1,5 @@
+use crate::{cli::Tui, repository::TodoRepository, tasks::error::TaskError};
+
+pub fn handle_tui(_params: Tui, repo: TodoRepository) -> Result<(), TaskError> {
+    crate::tui::run(repo)
+}
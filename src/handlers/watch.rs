@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use crate::{repository::TodoRepository, tasks::error::TaskError, watch::Watcher};
+
+/// Long enough for an editor to finish saving, short enough to feel immediate.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Run `draw` once, or with `watch` again after each change to the todo file on a cleared
+/// screen. The tasks are read again by `draw` every time.
+pub(super) fn redraw_on_change(
+    repo: &TodoRepository,
+    watch: bool,
+    mut draw: impl FnMut() -> Result<(), TaskError>,
+) -> Result<(), TaskError> {
+    if !watch {
+        return draw();
+    }
+
+    let Some(path) = repo.watched_file() else {
+        return Err(TaskError::UnsupportedByBackend);
+    };
+
+    let mut watcher = Watcher::new(path, DEBOUNCE);
+    loop {
+        print!("\x1b[2J\x1b[H");
+        // The file may be read in the middle of a save, it's drawn again on the next change
+        if let Err(err) = draw() {
+            eprintln!("An error occured: {err}");
+        }
+
+        watcher.wait();
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    tasks::{error::TaskError, list::TaskList, task::Task},
+    utils::{find_executable_in, split_command},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    Add,
+    Done,
+    Modify,
+    Remove,
+}
+
+impl Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookEvent::Add => f.write_str("add"),
+            HookEvent::Done => f.write_str("done"),
+            HookEvent::Modify => f.write_str("modify"),
+            HookEvent::Remove => f.write_str("remove"),
+        }
+    }
+}
+
+/// A task line before and after a command, `old` is empty for added tasks and `new` for removed
+/// ones.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    pub id: usize,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HookInput<'a> {
+    event: String,
+    todo_file: &'a Path,
+    changes: &'a [Change],
+}
+
+#[derive(Deserialize)]
+struct HookOutput {
+    changes: Vec<Change>,
+}
+
+/// Scripts run before and after the tasks are saved. They're configured in the `[hooks]` table of
+/// the config file, e.g. `pre-add = ["require-project"]`, or are executables in
+/// `<todo_dir>/hooks/` named after the event like `post-done`.
+pub struct Hooks {
+    hooks_dir: PathBuf,
+    todo_file: PathBuf,
+    configured: BTreeMap<String, Vec<String>>,
+}
+
+impl Hooks {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            hooks_dir: config.todo_dir().join("hooks"),
+            todo_file: config.todo_file(),
+            configured: config.hooks.clone(),
+        }
+    }
+
+    fn commands(&self, stage: &str, event: HookEvent) -> Vec<Vec<String>> {
+        let name = format!("{stage}-{event}");
+
+        let mut commands: Vec<Vec<String>> = self
+            .configured
+            .get(&name)
+            .into_iter()
+            .flatten()
+            .map(|command| split_command(command))
+            .filter(|command| !command.is_empty())
+            .collect();
+
+        if let Some(script) = find_executable_in([self.hooks_dir.clone()], &name) {
+            commands.push(vec![script.to_string_lossy().into_owned()]);
+        }
+
+        commands
+    }
+
+    pub fn has_hooks(&self, event: HookEvent) -> bool {
+        !self.commands("pre", event).is_empty() || !self.commands("post", event).is_empty()
+    }
+
+    /// Run the pre hooks one after the other, each one can veto the change by exiting with an
+    /// error, or rewrite it by printing the changes, in the same JSON format, on its stdout.
+    ///
+    /// Exiting with an error rejects the whole batch. To veto a single change, a hook prints it
+    /// back with `new` set to its `old` line, `null` for an added task. The changes it leaves out
+    /// of its output are saved as they are.
+    pub fn run_pre(
+        &self,
+        event: HookEvent,
+        changes: Vec<Change>,
+    ) -> Result<Vec<Change>, TaskError> {
+        let mut changes = changes;
+
+        for command in self.commands("pre", event) {
+            let output = self.run(&command, event, &changes)?;
+            if !output.status.success() {
+                eprintln!("The hook `{}` rejected the change", command.join(" "));
+                return Err(TaskError::HookRejected);
+            }
+
+            if output.stdout.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let Ok(rewritten) = serde_json::from_slice::<HookOutput>(&output.stdout) else {
+                eprintln!("The hook `{}` printed invalid JSON", command.join(" "));
+                return Err(TaskError::InvalidHookOutput);
+            };
+            changes = rewritten.changes;
+        }
+
+        Ok(changes)
+    }
+
+    /// Run the post hooks, their failures are reported but the change is already saved.
+    pub fn run_post(&self, event: HookEvent, changes: &[Change]) {
+        for command in self.commands("post", event) {
+            match self.run(&command, event, changes) {
+                Ok(output) if !output.status.success() => {
+                    eprintln!("The hook `{}` failed: {}", command.join(" "), output.status)
+                }
+                Ok(output) => {
+                    // Post hooks can't rewrite anything, just show what they have to say
+                    let _ = std::io::stdout().write_all(&output.stdout);
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn run(
+        &self,
+        command: &[String],
+        event: HookEvent,
+        changes: &[Change],
+    ) -> Result<std::process::Output, TaskError> {
+        let input = HookInput {
+            event: event.to_string(),
+            todo_file: &self.todo_file,
+            changes,
+        };
+        let Ok(input) = serde_json::to_vec(&input) else {
+            return Err(TaskError::FailedToRunHook);
+        };
+
+        let (program, args) = command.split_first().ok_or(TaskError::FailedToRunHook)?;
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                eprintln!("Failed to run the hook `{program}`: {err}");
+                return Err(TaskError::FailedToRunHook);
+            }
+        };
+
+        // The hook may not read its stdin at all, so a broken pipe isn't an error
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(&input);
+        }
+
+        child.wait_with_output().map_err(|err| {
+            eprintln!("Failed to run the hook `{program}`: {err}");
+            TaskError::FailedToRunHook
+        })
+    }
+}
+
+/// Compare the tasks by id to find the ones that were added, modified or removed.
+pub fn diff(old: &TaskList, new: &TaskList) -> Vec<Change> {
+    let new_lines: HashMap<usize, String> =
+        new.iter().map(|task| (task.id, task.to_string())).collect();
+    let old_ids: HashSet<usize> = old.iter().map(|task| task.id).collect();
+
+    let mut changes: Vec<Change> = old
+        .iter()
+        .filter_map(|task| {
+            let old_line = task.to_string();
+            let new_line = new_lines.get(&task.id);
+            (new_line != Some(&old_line)).then(|| Change {
+                id: task.id,
+                old: Some(old_line),
+                new: new_line.cloned(),
+            })
+        })
+        .collect();
+
+    changes.extend(
+        new.iter()
+            .filter(|task| !old_ids.contains(&task.id))
+            .map(|task| Change {
+                id: task.id,
+                old: None,
+                new: Some(task.to_string()),
+            }),
+    );
+
+    changes
+}
+
+/// Apply the changes returned by the pre hooks to the task list.
+pub fn apply(tasks: &mut TaskList, changes: &[Change]) -> Result<(), TaskError> {
+    for change in changes {
+        let position = tasks.iter().position(|task| task.id == change.id);
+
+        match (&change.new, position) {
+            (Some(line), Some(idx)) => tasks[idx] = Task::from_str(change.id, line)?,
+            (Some(line), None) => tasks.push(Task::from_str(change.id, line)?),
+            (None, Some(idx)) => {
+                tasks.remove(idx);
+            }
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{Change, HookEvent, Hooks, apply, diff};
+    use crate::config::Config;
+    use crate::storage::TaskStorage;
+    use crate::tasks::{list::TaskList, task::Task};
+    use std::os::unix::fs::PermissionsExt;
+
+    fn tasks(lines: &[(usize, &str)]) -> TaskList {
+        lines
+            .iter()
+            .map(|(id, line)| Task::from_str(*id, line).unwrap())
+            .collect()
+    }
+
+    fn hooks_with(name: &str, script: &str) -> (tempfile::TempDir, Hooks) {
+        let todo_dir = tempfile::tempdir().unwrap();
+        let hooks_dir = todo_dir.path().join("hooks");
+        std::fs::create_dir(&hooks_dir).unwrap();
+
+        let hook = hooks_dir.join(name);
+        std::fs::write(&hook, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = Config {
+            todo_dir: todo_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+
+        (todo_dir, Hooks::new(&config))
+    }
+
+    #[test]
+    fn it_finds_added_modified_and_removed_tasks() {
+        let old = tasks(&[(1, "one"), (2, "two"), (3, "three")]);
+        let new = tasks(&[(1, "one"), (2, "x two"), (4, "four")]);
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Change {
+                    id: 2,
+                    old: Some("two".to_string()),
+                    new: Some("x two".to_string())
+                },
+                Change {
+                    id: 3,
+                    old: Some("three".to_string()),
+                    new: None
+                },
+                Change {
+                    id: 4,
+                    old: None,
+                    new: Some("four".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_applies_rewritten_changes() {
+        let mut list = tasks(&[(1, "one"), (2, "two")]);
+        let changes = vec![
+            Change {
+                id: 1,
+                old: Some("one".to_string()),
+                new: None,
+            },
+            Change {
+                id: 2,
+                old: Some("two".to_string()),
+                new: Some("(A) two".to_string()),
+            },
+        ];
+
+        apply(&mut list, &changes).unwrap();
+
+        assert_eq!(list, tasks(&[(2, "(A) two")]));
+    }
+
+    #[test]
+    fn it_lets_pre_hooks_veto_changes() {
+        let (_dir, hooks) = hooks_with("pre-add", "grep -q '+' || exit 1");
+        let change = |line: &str| Change {
+            id: 1,
+            old: None,
+            new: Some(line.to_string()),
+        };
+
+        assert!(
+            hooks
+                .run_pre(HookEvent::Add, vec![change("no project")])
+                .is_err()
+        );
+        assert!(
+            hooks
+                .run_pre(HookEvent::Add, vec![change("with +project")])
+                .is_ok()
+        );
+        assert!(
+            hooks
+                .run_pre(HookEvent::Done, vec![change("no project")])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn it_lets_pre_hooks_rewrite_changes() {
+        let (_dir, hooks) = hooks_with(
+            "pre-add",
+            r#"echo '{"changes":[{"id":1,"old":null,"new":"(A) rewritten"}]}'"#,
+        );
+        let changes = vec![Change {
+            id: 1,
+            old: None,
+            new: Some("original".to_string()),
+        }];
+
+        let changes = hooks.run_pre(HookEvent::Add, changes).unwrap();
+
+        assert_eq!(changes[0].new.as_deref(), Some("(A) rewritten"));
+    }
+
+    #[test]
+    fn it_keeps_a_removed_task_vetoed_by_pre_hooks_in_place() {
+        let (dir, hooks) = hooks_with(
+            "pre-remove",
+            r#"echo '{"changes":[{"id":1,"old":"one","new":"one"}]}'"#,
+        );
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(&todo_file, "one\ntwo\nthree\n").unwrap();
+
+        let storage = TaskStorage::new(todo_file.clone()).with_hooks(hooks);
+        storage
+            .remove_with_hooks(&tasks(&[(1, "one"), (2, "two")]))
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(todo_file).unwrap(), "one\nthree\n");
+    }
+
+    #[test]
+    fn it_appends_the_task_rewritten_by_pre_hooks() {
+        let (dir, hooks) = hooks_with(
+            "pre-add",
+            r#"echo '{"changes":[{"id":2,"old":null,"new":"(A) rewritten"}]}'"#,
+        );
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(&todo_file, "first").unwrap();
+
+        let storage = TaskStorage::new(todo_file.clone()).with_hooks(hooks);
+        let added = storage
+            .append_with_hooks(Task::from_str(2, "original").unwrap())
+            .unwrap();
+
+        assert_eq!(added, tasks(&[(2, "(A) rewritten")]));
+        assert_eq!(
+            std::fs::read_to_string(todo_file).unwrap(),
+            "first\n(A) rewritten\n"
+        );
+    }
+}
@@ -1,6 +1,23 @@
+pub mod aliases;
+pub mod caldav;
+pub mod calendar;
 pub mod cli;
 pub mod config;
+pub mod formats;
+pub mod git;
 pub mod handlers;
+pub mod hooks;
+pub mod merge;
+pub mod plugins;
+pub mod remind;
+pub mod reports;
+pub mod repository;
+pub mod server;
 pub mod storage;
 pub mod tasks;
+pub mod timer;
+pub mod tui;
 pub mod utils;
+pub mod watch;
+
+pub use repository::{TaskUpdate, TodoRepository};
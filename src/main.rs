@@ -1,43 +1,156 @@
+use std::collections::HashSet;
+use std::path::Path;
+
 use clap::Parser;
-use std::path::PathBuf;
 use todors::{
-    cli::{Cli, Commands},
+    aliases::{expand_alias, expand_views},
+    cli::{Cli, Commands, ConfigArgs},
     config::Config,
+    git::GitHistory,
     handlers::*,
+    hooks::Hooks,
+    plugins::{find_plugin, run_plugin},
+    repository::TodoRepository,
     storage::TaskStorage,
+    tasks::error::TaskError,
+    timer::TimerFile,
 };
 
 fn main() {
-    let cli = Cli::parse();
-
-    let config_file_path = if let Some(path) = &cli.config_path {
-        // FIXME: is there a way to not clone here
-        path.clone()
-    } else if let Ok(path) = std::env::var("XDG_CONFIG_HOME") {
-        PathBuf::from(format!("{path}/todors/config.toml"))
-    } else if let Ok(home_path) = std::env::var("HOME") {
-        PathBuf::from(format!("{home_path}/.config/todors/config.toml"))
-    } else {
-        std::process::exit(1);
+    let mut args: Vec<String> = std::env::args().collect();
+    let mut cli = Cli::parse_from(&args);
+
+    // Run as a git merge driver, it must not depend on the config or create any file
+    if let Commands::Merge(params) = cli.command {
+        if let Err(err) = handle_merge(params) {
+            eprintln!("An error occured: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let config_file_path = match cli
+        .config_path
+        .clone()
+        .map_or_else(Config::default_path, Ok)
+    {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("An error occured: {err}");
+            std::process::exit(1);
+        }
     };
 
-    let config = Config::from_path(config_file_path);
-    let storage = TaskStorage::new(config.todo_file());
+    // The config command must work even when the config file is missing or invalid
+    if let Commands::Config(params) = cli.command {
+        return run_config(params, &config_file_path, &cli.overrides);
+    }
+
+    let config = match Config::load(&config_file_path, &cli.overrides) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("An error occured: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    // Aliases and views are expanded in the raw args, then parsed again by clap. Commands that
+    // aren't aliases are run as plugins.
+    let mut seen = HashSet::new();
+    while let Commands::External(external) = &cli.command {
+        let name = &external[0];
+        let Some(alias) = config.aliases.get(name) else {
+            let Some(plugin) = find_plugin(name, &config) else {
+                eprintln!(
+                    "An error occured: {}",
+                    TaskError::UnknownCommand(name.clone())
+                );
+                std::process::exit(2);
+            };
+
+            match run_plugin(&plugin, &external[1..], &config, &config_file_path) {
+                Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+                Err(err) => {
+                    eprintln!("An error occured: {err}");
+                    std::process::exit(1);
+                }
+            }
+        };
+        if !seen.insert(name.clone()) {
+            eprintln!(
+                "An error occured: {}",
+                TaskError::RecursiveAlias(name.clone())
+            );
+            std::process::exit(2);
+        }
+
+        args = expand_alias(&args, external, alias);
+        cli = Cli::parse_from(&args);
+    }
+
+    if !matches!(cli.command, Commands::Add(_)) && args.iter().any(|arg| arg.starts_with("@view:"))
+    {
+        match expand_views(&args, &config.views) {
+            Ok(expanded) => cli = Cli::parse_from(expanded),
+            Err(err) => {
+                eprintln!("An error occured: {err}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let storage = match TaskStorage::from_config(&config) {
+        Ok(storage) => storage.with_hooks(Hooks::new(&config)),
+        Err(err) => {
+            eprintln!("An error occured: {err}");
+            std::process::exit(1);
+        }
+    };
+    let repo = TodoRepository::new(storage).with_history(GitHistory::new(&config));
 
     let result = match cli.command {
-        Commands::Add(params) => handle_add(params, storage),
-        Commands::Done(params) => handle_done(params, storage),
-        Commands::List(params) => handle_list(params, storage),
-        Commands::Remove(params) => handle_remove(params, storage),
-        Commands::Edit(params) => handle_edit(params, config),
-        Commands::Due(params) => handle_due(params, storage),
-        Commands::Undone(params) => handle_undone(params, storage),
-        Commands::Clean(params) => handle_clean(params, storage),
-        Commands::Modify(params) => handle_modify(params, storage),
-        Commands::Next(params) => handle_next(params, storage),
+        Commands::Add(params) => handle_add(params, repo),
+        Commands::Done(params) => handle_done(params, repo),
+        Commands::List(params) => handle_list(params, repo),
+        Commands::Remove(params) => handle_remove(params, repo),
+        Commands::Edit(params) => handle_edit(params, repo, config),
+        Commands::Due(params) => handle_due(params, repo),
+        Commands::Cal(params) => handle_cal(params, repo, config.done_file()),
+        Commands::Undone(params) => handle_undone(params, repo),
+        Commands::Clean(params) => handle_clean(params, repo),
+        Commands::Modify(params) => handle_modify(params, repo),
+        Commands::Next(params) => handle_next(params, repo),
+        Commands::Tui(params) => handle_tui(params, repo),
+        Commands::Import(params) => handle_import(params, repo),
+        Commands::Export(params) => handle_export(params, repo),
+        Commands::Sync(params) => handle_sync(params, config),
+        Commands::Caldav(params) => handle_caldav(params, config),
+        Commands::Serve(params) => handle_serve(params, repo, config.server),
+        Commands::Start(params) => handle_start(params, repo, TimerFile::new(config.timer_file())),
+        Commands::Stop(params) => handle_stop(params, repo, TimerFile::new(config.timer_file())),
+        Commands::Report(params) => handle_report(params, repo, config.done_file()),
+        Commands::Stats(params) => handle_stats(params, repo, config.done_file()),
+        Commands::Remind(params) => {
+            let state_file = config.remind_state_file();
+            handle_remind(params, repo, config.remind, state_file)
+        }
+        Commands::Config(params) => {
+            return run_config(params, &config_file_path, &cli.overrides);
+        }
+        Commands::Merge(_) => unreachable!("Merges run before the config is loaded"),
+        Commands::External(_) => unreachable!("Aliases are expanded before running the command"),
     };
 
+    // Non-zero so scripts can tell the command failed
     if let Err(err) = result {
         eprintln!("An error occured: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run_config(params: ConfigArgs, config_file_path: &Path, overrides: &[(String, String)]) {
+    if let Err(err) = handle_config(params, config_file_path, overrides) {
+        eprintln!("An error occured: {err}");
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,349 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use crate::tasks::{error::TaskError, task::Task};
+
+/// How a task is recognized across the versions of a file: its `id:` tag when it has one, else
+/// its subject, which stays the same when it's completed, prioritized or rescheduled. The
+/// occurrence tells duplicated subjects apart. A task whose subject was edited is matched by its
+/// position instead, see [`match_edited`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Id(String),
+    Subject(String, usize),
+}
+
+/// A line of the merged file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Merged {
+    Task(Task),
+    /// Both sides changed the task differently, `None` when a side removed it
+    Conflict {
+        ours: Option<Task>,
+        theirs: Option<Task>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MergeResult {
+    pub lines: Vec<Merged>,
+}
+
+impl MergeResult {
+    pub fn conflicts(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| matches!(line, Merged::Conflict { .. }))
+            .count()
+    }
+}
+
+/// The merged todo.txt file, conflicts are written with git's markers.
+impl Display for MergeResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                Merged::Task(task) => writeln!(f, "{task}")?,
+                Merged::Conflict { ours, theirs } => {
+                    writeln!(f, "<<<<<<< ours")?;
+                    if let Some(task) = ours {
+                        writeln!(f, "{task}")?;
+                    }
+                    writeln!(f, "=======")?;
+                    if let Some(task) = theirs {
+                        writeln!(f, "{task}")?;
+                    }
+                    writeln!(f, ">>>>>>> theirs")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse(content: &str) -> Result<Vec<(Key, Task)>, TaskError> {
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut tasks = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let task = Task::from_str(idx + 1, line)?;
+        let key = match task.tags.get("id") {
+            Some(id) => Key::Id(id.clone()),
+            None => {
+                let occurrence = occurrences.entry(task.subject.clone()).or_default();
+                *occurrence += 1;
+                Key::Subject(task.subject.clone(), *occurrence)
+            }
+        };
+
+        tasks.push((key, task));
+    }
+
+    Ok(tasks)
+}
+
+/// Whether two subjects have a word in common, an edit rarely rewrites the whole subject.
+fn similar(a: &str, b: &str) -> bool {
+    let words: HashSet<String> = a.split_whitespace().map(str::to_lowercase).collect();
+
+    b.split_whitespace()
+        .any(|word| words.contains(&word.to_lowercase()))
+}
+
+/// Give the tasks of `side` whose subject was edited the key they had in `base`. Like the lines
+/// of a diff, a task removed from `base` and one added to `side` between the same unchanged
+/// neighbours are taken as the same task, as long as their subjects are similar.
+fn match_edited(base: &[(Key, Task)], side: &mut [(Key, Task)]) {
+    let base_keys: HashSet<&Key> = base.iter().map(|(key, _)| key).collect();
+    let side_keys: HashSet<Key> = side.iter().map(|(key, _)| key.clone()).collect();
+
+    // The removed tasks by the last unchanged task before them
+    let mut removed: HashMap<Option<&Key>, Vec<&Key>> = HashMap::new();
+    let mut neighbour = None;
+    for (key, _) in base {
+        if side_keys.contains(key) {
+            neighbour = Some(key);
+        } else if matches!(key, Key::Subject(..)) {
+            removed.entry(neighbour).or_default().push(key);
+        }
+    }
+
+    let mut neighbour = None;
+    for (key, task) in side.iter_mut() {
+        if let Some(unchanged) = base_keys.get(key) {
+            neighbour = Some(*unchanged);
+        } else if matches!(key, Key::Subject(..))
+            && let Some(candidates) = removed.get_mut(&neighbour)
+            && let Some(idx) = candidates.iter().position(|candidate| {
+                matches!(candidate, Key::Subject(subject, _) if similar(subject, &task.subject))
+            })
+        {
+            *key = candidates.remove(idx).clone();
+        }
+    }
+}
+
+/// Line numbers differ between the versions, only the content is compared.
+pub(crate) fn same(a: &Task, b: &Task) -> bool {
+    Task {
+        id: b.id,
+        ..a.clone()
+    } == *b
+}
+
+/// The side that changed a field wins, `None` when both changed it differently.
+fn merge_field<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours == theirs || base == theirs {
+        Some(ours.clone())
+    } else if base == ours {
+        Some(theirs.clone())
+    } else {
+        None
+    }
+}
+
+/// Merge the fields changed independently on each side, like a completion on one side and a new
+/// due date on the other.
+pub(crate) fn merge_task(base: &Task, ours: &Task, theirs: &Task) -> Option<Task> {
+    let (completed, completed_at) = merge_field(
+        &(base.completed, base.completed_at),
+        &(ours.completed, ours.completed_at),
+        &(theirs.completed, theirs.completed_at),
+    )?;
+    let (subject, contexts, projects) = merge_field(
+        &(&base.subject, &base.contexts, &base.projects),
+        &(&ours.subject, &ours.contexts, &ours.projects),
+        &(&theirs.subject, &theirs.contexts, &theirs.projects),
+    )?;
+
+    let (due_date, due_time) = merge_field(
+        &(base.due_date, base.due_time),
+        &(ours.due_date, ours.due_time),
+        &(theirs.due_date, theirs.due_time),
+    )?;
+
+    // Completing a task drops its priority, so it can't conflict with a new priority
+    let priority = if completed {
+        None
+    } else {
+        merge_field(&base.priority, &ours.priority, &theirs.priority)?
+    };
+
+    Some(Task {
+        id: ours.id,
+        subject: subject.clone(),
+        priority,
+        created_at: merge_field(&base.created_at, &ours.created_at, &theirs.created_at)?,
+        completed_at,
+        completed,
+        due_date,
+        due_time,
+        contexts: contexts.clone(),
+        projects: projects.clone(),
+        hashtags: merge_field(&base.hashtags, &ours.hashtags, &theirs.hashtags)?,
+        tags: merge_field(&base.tags, &ours.tags, &theirs.tags)?,
+    })
+}
+
+fn merge_one(base: Option<&Task>, ours: Option<&Task>, theirs: Option<&Task>) -> Option<Merged> {
+    let conflict = || Merged::Conflict {
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    };
+
+    match (base, ours, theirs) {
+        (_, None, None) => None,
+        (Some(base), Some(task), None) | (Some(base), None, Some(task)) => {
+            // Removed on one side, kept only if it was changed on the other
+            if same(base, task) {
+                None
+            } else {
+                Some(conflict())
+            }
+        }
+        (None, Some(task), None) | (None, None, Some(task)) => Some(Merged::Task(task.clone())),
+        (None, Some(ours), Some(theirs)) => {
+            if same(ours, theirs) {
+                Some(Merged::Task(ours.clone()))
+            } else {
+                Some(conflict())
+            }
+        }
+        (Some(base), Some(ours_task), Some(theirs_task)) => {
+            Some(merge_task(base, ours_task, theirs_task).map_or_else(conflict, Merged::Task))
+        }
+    }
+}
+
+/// Three-way merge of todo.txt files: tasks are matched by their `id:` tag, their subject or, once
+/// edited, their position. The changes made on a single side are kept and only tasks changed on
+/// both sides conflict. The result follows the order of `ours`, with the tasks only in `theirs`
+/// at the end.
+pub fn merge(base: &str, ours: &str, theirs: &str) -> Result<MergeResult, TaskError> {
+    let base = parse(base)?;
+    let mut ours = parse(ours)?;
+    let mut theirs = parse(theirs)?;
+    match_edited(&base, &mut ours);
+    match_edited(&base, &mut theirs);
+    let base: HashMap<Key, Task> = base.into_iter().collect();
+
+    let ours_keys: HashMap<&Key, &Task> = ours.iter().map(|(key, task)| (key, task)).collect();
+    let theirs_keys: HashMap<&Key, &Task> = theirs.iter().map(|(key, task)| (key, task)).collect();
+
+    let mut lines: Vec<Merged> = ours
+        .iter()
+        .filter_map(|(key, task)| {
+            merge_one(base.get(key), Some(task), theirs_keys.get(key).copied())
+        })
+        .collect();
+
+    lines.extend(
+        theirs
+            .iter()
+            .filter(|(key, _)| !ours_keys.contains_key(key))
+            .filter_map(|(key, task)| merge_one(base.get(key), None, Some(task))),
+    );
+
+    Ok(MergeResult { lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeResult, merge};
+
+    fn merged(base: &str, ours: &str, theirs: &str) -> MergeResult {
+        merge(base, ours, theirs).unwrap()
+    }
+
+    #[test]
+    fn it_keeps_changes_made_on_one_side() {
+        let base = "(A) call mom\nbuy milk\nwrite report\n";
+        let ours = "x 2024-06-02 2024-06-01 call mom\nbuy milk\nwrite report\nnew on ours\n";
+        let theirs = "(A) call mom\nbuy milk due:2024-06-10\nnew on theirs\n";
+
+        let result = merged(base, ours, theirs);
+
+        assert_eq!(result.conflicts(), 0);
+        assert_eq!(
+            result.to_string(),
+            "x 2024-06-02 2024-06-01 call mom\nbuy milk due:2024-06-10\nnew on ours\nnew on theirs\n"
+        );
+    }
+
+    #[test]
+    fn it_combines_a_completion_and_an_edit_of_the_same_task() {
+        let base = "(B) 2024-06-01 call mom\n";
+        let ours = "x 2024-06-02 2024-06-01 call mom\n";
+        let theirs = "(A) 2024-06-01 call mom due:2024-06-05\n";
+
+        let result = merged(base, ours, theirs);
+
+        assert_eq!(result.conflicts(), 0);
+        assert_eq!(
+            result.to_string(),
+            "x 2024-06-02 2024-06-01 call mom due:2024-06-05\n"
+        );
+    }
+
+    #[test]
+    fn it_matches_an_edited_subject_by_its_position() {
+        let base = "(B) call mom\nbuy milk\nwrite report\n";
+        let ours = "(B) call mom tonight\nbuy milk\nwrite report\n";
+        let theirs = "x 2024-06-02 call mom\nbuy milk\nwrite the report\n";
+
+        let result = merged(base, ours, theirs);
+
+        assert_eq!(result.conflicts(), 0);
+        assert_eq!(
+            result.to_string(),
+            "x 2024-06-02 call mom tonight\nbuy milk\nwrite the report\n"
+        );
+    }
+
+    #[test]
+    fn it_matches_tasks_by_their_id_tag() {
+        let base = "call mom id:1\n";
+        let ours = "call mom tonight id:1\n";
+        let theirs = "(A) call mom id:1\n";
+
+        assert_eq!(
+            merged(base, ours, theirs).to_string(),
+            "(A) call mom tonight id:1\n"
+        );
+    }
+
+    #[test]
+    fn it_reports_true_conflicts() {
+        let base = "call mom due:2024-06-01\nbuy milk\n";
+        let ours = "call mom due:2024-06-02\nbuy milk @store\n";
+        let theirs = "call mom due:2024-06-03\n";
+
+        let result = merged(base, ours, theirs);
+
+        // `buy milk` was edited on our side and removed on theirs
+        assert_eq!(result.conflicts(), 2);
+        assert_eq!(
+            result.to_string(),
+            "<<<<<<< ours\ncall mom due:2024-06-02\n=======\ncall mom due:2024-06-03\n>>>>>>> theirs\n<<<<<<< ours\nbuy milk @store\n=======\n>>>>>>> theirs\n"
+        );
+    }
+
+    #[test]
+    fn it_conflicts_when_a_changed_task_is_removed() {
+        let base = "call mom\n";
+        let ours = "(A) call mom\n";
+
+        let result = merged(base, ours, "");
+
+        assert_eq!(result.conflicts(), 1);
+        assert_eq!(
+            result.to_string(),
+            "<<<<<<< ours\n(A) call mom\n=======\n>>>>>>> theirs\n"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use crate::{
+    config::Config,
+    tasks::error::TaskError,
+    utils::{find_executable, find_executable_in},
+};
+
+const PLUGIN_PREFIX: &str = "todors-";
+
+/// Find the `todors-<name>` executable, first in `<todo_dir>/actions` like todo.txt-cli add-ons,
+/// then in the `PATH`.
+pub fn find_plugin(name: &str, config: &Config) -> Option<PathBuf> {
+    let executable = format!("{PLUGIN_PREFIX}{name}");
+
+    find_executable_in([config.todo_dir().join("actions")], &executable)
+        .or_else(|| find_executable(&executable))
+}
+
+/// Run a plugin with the remaining args. Like git and cargo, the context is passed with env vars
+/// so the plugin can read the same files or call `todors` back.
+pub fn run_plugin(
+    plugin: &Path,
+    args: &[String],
+    config: &Config,
+    config_file_path: &Path,
+) -> Result<ExitStatus, TaskError> {
+    let todo_file = config.todo_file();
+    let list = todo_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut cmd = Command::new(plugin);
+    cmd.args(args)
+        .env("TODORS_TODO_FILE", &todo_file)
+        .env("TODORS_TODO_DIR", config.todo_dir())
+        .env("TODORS_CONFIG", config_file_path)
+        .env("TODORS_LIST", list)
+        // Same env as todo.txt-cli so its add-ons can be reused
+        .env("TODO_FILE", &todo_file)
+        .env("TODO_DIR", config.todo_dir());
+    if let Ok(exe) = std::env::current_exe() {
+        cmd.env("TODORS", exe);
+    }
+
+    match cmd.status() {
+        Ok(status) => Ok(status),
+        Err(err) => {
+            eprintln!("Failed to run the plugin {}: {err}", plugin.display());
+            Err(TaskError::FailedToRunPlugin)
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{find_plugin, run_plugin};
+    use crate::config::Config;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn it_runs_plugins_from_the_actions_dir() {
+        let todo_dir = tempfile::tempdir().unwrap();
+        let actions = todo_dir.path().join("actions");
+        std::fs::create_dir(&actions).unwrap();
+
+        let plugin = actions.join("todors-hello");
+        std::fs::write(
+            &plugin,
+            "#!/bin/sh\necho \"$TODORS_LIST $TODO_FILE $*\" > \"$TODORS_TODO_DIR/out\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = Config {
+            todo_dir: todo_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+
+        let found = find_plugin("hello", &config).unwrap();
+        assert_eq!(found, plugin);
+        assert!(find_plugin("missing", &config).is_none());
+
+        let status = run_plugin(
+            &found,
+            &["a".to_string(), "b".to_string()],
+            &config,
+            &todo_dir.path().join("config.toml"),
+        )
+        .unwrap();
+        assert!(status.success());
+
+        let out = std::fs::read_to_string(todo_dir.path().join("out")).unwrap();
+        assert_eq!(out, format!("todo {} a b\n", config.todo_file().display()));
+    }
+}
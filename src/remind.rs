@@ -0,0 +1,213 @@
+use std::{collections::BTreeSet, process::Command};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{tasks::task::Task, utils::split_command};
+
+/// A notification about a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// Identifies the reminder in the notified state, it changes with the date it's for
+    pub key: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// A `remind:` tag, `2024-06-01T09:00` or a whole day with `2024-06-01`.
+fn parse_remind(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()
+        .or_else(|| {
+            let date = value.parse::<NaiveDate>().ok()?;
+            Some(date.and_time(NaiveTime::MIN))
+        })
+}
+
+/// Tells the task apart in the reminder keys: its `id:` tag when it has one, tasks with the same
+/// subject are otherwise reminded of once.
+fn task_key(task: &Task) -> &str {
+    task.stable_id().unwrap_or(&task.subject)
+}
+
+/// The reminders of the open tasks at `now`: the tasks due today or overdue, and those whose
+/// `remind:` time has come.
+pub fn reminders(tasks: &[Task], now: NaiveDateTime) -> Vec<Reminder> {
+    let today = now.date();
+    let mut reminders = Vec::new();
+
+    for task in tasks.iter().filter(|task| !task.completed) {
+        let body = format!("{}) {}", task.id, task.subject);
+
+        if let Some(due) = task.due_date.filter(|due| *due <= today) {
+            let title = match task.due_time_of_day() {
+                _ if due < today => "Overdue".to_string(),
+                Some(time) => format!("Due today at {}", time.format("%H:%M")),
+                None => "Due today".to_string(),
+            };
+            reminders.push(Reminder {
+                key: format!("due:{due}:{}", task_key(task)),
+                title,
+                body: body.clone(),
+            });
+        }
+
+        if let Some(remind) = task.tags.get("remind")
+            && parse_remind(remind).is_some_and(|at| at <= now)
+        {
+            reminders.push(Reminder {
+                key: format!("remind:{remind}:{}", task_key(task)),
+                title: "Reminder".to_string(),
+                body,
+            });
+        }
+    }
+
+    reminders
+}
+
+/// Send the reminders that weren't yet, `notify` returns whether it succeeded so a failed one is
+/// retried next time. Reminders that no longer apply are forgotten so they can happen again,
+/// e.g. for a task undone after being completed. Returns how many were sent.
+pub fn remind(
+    tasks: &[Task],
+    now: NaiveDateTime,
+    notified: &mut BTreeSet<String>,
+    mut notify: impl FnMut(&Reminder) -> bool,
+) -> usize {
+    let reminders = reminders(tasks, now);
+    notified.retain(|key| reminders.iter().any(|reminder| reminder.key == *key));
+
+    let mut sent = 0;
+    for reminder in reminders {
+        if !notified.contains(&reminder.key) && notify(&reminder) {
+            notified.insert(reminder.key);
+            sent += 1;
+        }
+    }
+
+    sent
+}
+
+/// Run the configured command with the title and the body of the reminder as last arguments.
+pub fn notify(command: &str, reminder: &Reminder) -> bool {
+    let command = split_command(command);
+    let Some((program, args)) = command.split_first() else {
+        eprintln!("The remind command is empty");
+        return false;
+    };
+
+    match Command::new(program)
+        .args(args)
+        .arg(&reminder.title)
+        .arg(&reminder.body)
+        .status()
+    {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            eprintln!("The remind command `{program}` failed: {status}");
+            false
+        }
+        Err(err) => {
+            eprintln!("Failed to run the remind command `{program}`: {err}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use super::{remind, reminders};
+    use crate::tasks::task::Task;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 6, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn tasks(lines: &[&str]) -> Vec<Task> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_reminds_of_due_tasks_and_remind_tags() {
+        let tasks = tasks(&[
+            "pay rent due:2024-06-01",
+            "call the bank due:2024-06-03",
+            "book flights due:2024-06-20",
+            "x 2024-06-01 send invoice due:2024-06-01",
+            "standup remind:2024-06-03T09:00",
+            "water plants remind:2024-06-03T18:00",
+            "renew passport remind:2024-06-02",
+        ]);
+
+        let titles: Vec<(String, String)> = reminders(&tasks, at(3, 10))
+            .into_iter()
+            .map(|reminder| (reminder.title, reminder.body))
+            .collect();
+
+        assert_eq!(
+            titles,
+            [
+                ("Overdue", "1) pay rent"),
+                ("Due today", "2) call the bank"),
+                ("Reminder", "5) standup"),
+                ("Reminder", "7) renew passport"),
+            ]
+            .map(|(title, body)| (title.to_string(), body.to_string()))
+        );
+    }
+
+    #[test]
+    fn it_only_notifies_once() {
+        let tasks = tasks(&["pay rent due:2024-06-01", "call the bank due:2024-06-03"]);
+        let mut notified = BTreeSet::new();
+
+        assert_eq!(remind(&tasks, at(1, 8), &mut notified, |_| true), 1);
+        assert_eq!(remind(&tasks, at(1, 9), &mut notified, |_| true), 0);
+        assert_eq!(remind(&tasks, at(3, 8), &mut notified, |_| true), 1);
+        assert_eq!(notified.len(), 2);
+    }
+
+    #[test]
+    fn it_tells_tasks_with_the_same_subject_apart_by_their_id() {
+        let tasks = tasks(&[
+            "pay rent due:2024-06-01 id:flat",
+            "pay rent due:2024-06-01 id:garage",
+        ]);
+        let mut notified = BTreeSet::new();
+
+        assert_eq!(remind(&tasks, at(1, 8), &mut notified, |_| true), 2);
+    }
+
+    #[test]
+    fn it_retries_failed_notifications() {
+        let tasks = tasks(&["pay rent due:2024-06-01"]);
+        let mut notified = BTreeSet::new();
+
+        assert_eq!(remind(&tasks, at(1, 8), &mut notified, |_| false), 0);
+        assert_eq!(remind(&tasks, at(1, 9), &mut notified, |_| true), 1);
+    }
+
+    #[test]
+    fn it_notifies_again_when_the_due_date_changes() {
+        let mut notified = BTreeSet::new();
+
+        let before = tasks(&["pay rent due:2024-06-01"]);
+        assert_eq!(remind(&before, at(1, 8), &mut notified, |_| true), 1);
+
+        let postponed = tasks(&["pay rent due:2024-06-02"]);
+        assert_eq!(remind(&postponed, at(1, 9), &mut notified, |_| true), 0);
+        assert!(notified.is_empty());
+        assert_eq!(remind(&postponed, at(2, 8), &mut notified, |_| true), 1);
+    }
+}
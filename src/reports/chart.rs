@@ -0,0 +1,44 @@
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One character per value, as high as the value relative to the largest one.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+
+    values
+        .iter()
+        .map(|value| match max {
+            0 => SPARKS[0],
+            max => SPARKS[value * (SPARKS.len() - 1) / max],
+        })
+        .collect()
+}
+
+/// A horizontal bar of up to `width` characters, as long as `value` relative to `max`.
+pub fn bar(value: usize, max: usize, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+
+    // Round up so any non-zero value is visible
+    "█".repeat((value * width).div_ceil(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bar, sparkline};
+
+    #[test]
+    fn it_draws_sparklines() {
+        assert_eq!(sparkline(&[0, 1, 2, 7, 14]), "▁▁▂▄█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn it_draws_bars() {
+        assert_eq!(bar(5, 10, 10), "█████");
+        assert_eq!(bar(1, 100, 10), "█");
+        assert_eq!(bar(0, 10, 10), "");
+        assert_eq!(bar(0, 0, 10), "");
+    }
+}
@@ -0,0 +1,10 @@
+mod chart;
+mod stats;
+mod time;
+
+pub use chart::{bar, sparkline};
+pub use stats::{
+    Activity, Breakdown, breakdown, burndown, daily_activity, median_lead_time, overdue,
+    weekly_activity,
+};
+pub use time::{TimeEntry, TimeSheet, time_report};
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, NaiveDate};
+
+use crate::{cli::GroupBy, tasks::task::Task};
+
+/// The tasks created and completed over a day or a week.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activity {
+    /// The day, or the Monday of the week
+    pub date: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+}
+
+/// The tasks of a project or context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    pub name: String,
+    pub open: usize,
+    pub completed: usize,
+    pub overdue: usize,
+}
+
+fn is_overdue(task: &Task, today: NaiveDate) -> bool {
+    !task.completed && task.due_date.is_some_and(|due| due < today)
+}
+
+/// `count` dates going back from `last` by `step` days, oldest first. It stops at the earliest
+/// date that can be represented.
+fn dates_back(last: NaiveDate, count: u64, step: u64) -> Vec<NaiveDate> {
+    let mut dates: Vec<NaiveDate> = (0..count)
+        .map_while(|n| last.checked_sub_days(Days::new(n.checked_mul(step)?)))
+        .collect();
+    dates.reverse();
+
+    dates
+}
+
+fn monday(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+fn activity(
+    tasks: &[Task],
+    periods: impl IntoIterator<Item = NaiveDate>,
+    period: impl Fn(NaiveDate) -> NaiveDate,
+) -> Vec<Activity> {
+    let mut activity: BTreeMap<NaiveDate, Activity> = periods
+        .into_iter()
+        .map(|date| {
+            let activity = Activity {
+                date,
+                created: 0,
+                completed: 0,
+            };
+            (date, activity)
+        })
+        .collect();
+
+    for task in tasks {
+        if let Some(created) = task
+            .created_at
+            .and_then(|date| activity.get_mut(&period(date)))
+        {
+            created.created += 1;
+        }
+
+        if let Some(completed) = task
+            .completed_at
+            .filter(|_| task.completed)
+            .and_then(|date| activity.get_mut(&period(date)))
+        {
+            completed.completed += 1;
+        }
+    }
+
+    activity.into_values().collect()
+}
+
+/// The activity of each of the `days` days up to `today`, oldest first.
+pub fn daily_activity(tasks: &[Task], days: u64, today: NaiveDate) -> Vec<Activity> {
+    activity(tasks, dates_back(today, days, 1), |date| date)
+}
+
+/// The activity of each of the `weeks` weeks up to the one of `today`, oldest first.
+pub fn weekly_activity(tasks: &[Task], weeks: u64, today: NaiveDate) -> Vec<Activity> {
+    activity(tasks, dates_back(monday(today), weeks, 7), monday)
+}
+
+/// The median number of days between the creation and the completion of the tasks.
+pub fn median_lead_time(tasks: &[Task]) -> Option<f64> {
+    let mut lead_times: Vec<i64> = tasks
+        .iter()
+        .filter(|task| task.completed)
+        .filter_map(|task| Some((task.completed_at? - task.created_at?).num_days()))
+        .collect();
+    lead_times.sort_unstable();
+
+    let middle = lead_times.len() / 2;
+    match lead_times.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((lead_times[middle - 1] + lead_times[middle]) as f64 / 2.0),
+        _ => Some(lead_times[middle] as f64),
+    }
+}
+
+pub fn overdue(tasks: &[Task], today: NaiveDate) -> usize {
+    tasks.iter().filter(|task| is_overdue(task, today)).count()
+}
+
+/// The tasks per project or context, the busiest first. A task in several groups counts in each
+/// of them.
+pub fn breakdown(tasks: &[Task], by: GroupBy, today: NaiveDate) -> Vec<Breakdown> {
+    let mut groups: BTreeMap<String, Breakdown> = BTreeMap::new();
+
+    for task in tasks {
+        let names: Vec<String> = match by {
+            GroupBy::Project => task.projects.iter().map(|p| format!("+{p}")).collect(),
+            GroupBy::Context => task.contexts.iter().map(|c| format!("@{c}")).collect(),
+        };
+        let names = if names.is_empty() {
+            vec!["(none)".to_string()]
+        } else {
+            names
+        };
+
+        for name in names {
+            let group = groups.entry(name.clone()).or_insert(Breakdown {
+                name,
+                open: 0,
+                completed: 0,
+                overdue: 0,
+            });
+
+            if task.completed {
+                group.completed += 1;
+            } else {
+                group.open += 1;
+            }
+            if is_overdue(task, today) {
+                group.overdue += 1;
+            }
+        }
+    }
+
+    let mut groups: Vec<Breakdown> = groups.into_values().collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.open + group.completed));
+
+    groups
+}
+
+/// The number of open tasks at the end of each of the `days` days up to `today`, oldest first.
+/// Tasks without a creation date are counted from the start.
+pub fn burndown(tasks: &[Task], days: u64, today: NaiveDate) -> Vec<(NaiveDate, usize)> {
+    dates_back(today, days, 1)
+        .into_iter()
+        .map(|day| {
+            let open = tasks
+                .iter()
+                .filter(|task| task.created_at.is_none_or(|created| created <= day))
+                .filter(|task| match (task.completed, task.completed_at) {
+                    (false, _) => true,
+                    (true, Some(completed)) => completed > day,
+                    (true, None) => false,
+                })
+                .count();
+
+            (day, open)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{
+        Activity, Breakdown, breakdown, burndown, daily_activity, median_lead_time, overdue,
+        weekly_activity,
+    };
+    use crate::{cli::GroupBy, tasks::task::Task};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        [
+            "x 2024-06-03 2024-06-01 write report +work @laptop",
+            "x 2024-06-10 2024-06-03 fix the build +work",
+            "2024-06-03 call the bank @phone due:2024-06-05",
+            "2024-06-10 plan holidays +family due:2024-06-20",
+            "no dates +work",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+        .collect()
+    }
+
+    fn activity(day: u32, created: usize, completed: usize) -> Activity {
+        Activity {
+            date: date(day),
+            created,
+            completed,
+        }
+    }
+
+    #[test]
+    fn it_counts_the_daily_activity() {
+        assert_eq!(
+            daily_activity(&tasks(), 3, date(3)),
+            [activity(1, 1, 0), activity(2, 0, 0), activity(3, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn it_counts_the_weekly_activity() {
+        // 2024-06-03 and 2024-06-10 are Mondays
+        let first_week = Activity {
+            date: NaiveDate::from_ymd_opt(2024, 5, 27).unwrap(),
+            created: 1,
+            completed: 0,
+        };
+
+        assert_eq!(
+            weekly_activity(&tasks(), 3, date(12)),
+            [first_week, activity(3, 2, 1), activity(10, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn it_computes_the_median_lead_time() {
+        assert_eq!(median_lead_time(&tasks()), Some(4.5));
+        assert_eq!(median_lead_time(&tasks()[..1]), Some(2.0));
+        assert_eq!(median_lead_time(&tasks()[2..]), None);
+    }
+
+    #[test]
+    fn it_counts_overdue_tasks() {
+        assert_eq!(overdue(&tasks(), date(12)), 1);
+        assert_eq!(overdue(&tasks(), date(21)), 2);
+    }
+
+    #[test]
+    fn it_breaks_down_the_tasks_per_project() {
+        let breakdown = breakdown(&tasks(), GroupBy::Project, date(12));
+
+        assert_eq!(
+            breakdown[0],
+            Breakdown {
+                name: "+work".to_string(),
+                open: 1,
+                completed: 2,
+                overdue: 0,
+            }
+        );
+        assert_eq!(breakdown[1].name, "(none)");
+        assert_eq!(breakdown[1].overdue, 1);
+    }
+
+    #[test]
+    fn it_computes_the_burndown() {
+        assert_eq!(
+            burndown(&tasks(), 4, date(10)),
+            [(date(7), 3), (date(8), 3), (date(9), 3), (date(10), 3)]
+        );
+        assert_eq!(burndown(&tasks(), 1, date(3)), [(date(3), 3)]);
+    }
+
+    #[test]
+    fn it_stops_at_the_earliest_date() {
+        let today = NaiveDate::MIN + chrono::Days::new(2);
+
+        assert_eq!(daily_activity(&[], u64::MAX, today).len(), 3);
+        assert_eq!(burndown(&[], 10, today).first(), Some(&(NaiveDate::MIN, 0)));
+    }
+}
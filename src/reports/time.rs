@@ -0,0 +1,159 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::{
+    cli::GroupBy,
+    tasks::{
+        duration::{total_duration, total_estimate},
+        task::Task,
+    },
+};
+
+/// The time spent on a task, or on a group of tasks, and what was estimated for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeEntry {
+    pub name: String,
+    pub spent: TimeDelta,
+    /// `None` when none of the tasks has an estimate
+    pub estimate: Option<TimeDelta>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeSheet {
+    /// Longest first
+    pub entries: Vec<TimeEntry>,
+    /// A task in several groups only counts once in the total
+    pub total: TimeDelta,
+    pub estimate: Option<TimeDelta>,
+}
+
+/// The time spent per task, or per project or context with `group`. A task in several groups
+/// counts in each of them. The `spent:` tags aren't dated, with `since` only the tasks completed
+/// since then and the open ones are counted.
+pub fn time_report(tasks: &[Task], group: Option<GroupBy>, since: Option<NaiveDate>) -> TimeSheet {
+    let tracked: Vec<(&Task, TimeDelta)> = tasks
+        .iter()
+        .filter(|task| {
+            since.is_none_or(|since| {
+                !task.completed || task.completed_at.is_some_and(|d| d >= since)
+            })
+        })
+        .filter_map(|task| Some((task, task.spent()?)))
+        .collect();
+
+    let mut groups: BTreeMap<String, Vec<&Task>> = BTreeMap::new();
+    for (task, _) in &tracked {
+        let names = match group {
+            None => vec![task.subject.clone()],
+            Some(GroupBy::Project) => task.projects.iter().map(|p| format!("+{p}")).collect(),
+            Some(GroupBy::Context) => task.contexts.iter().map(|c| format!("@{c}")).collect(),
+        };
+        let names = if names.is_empty() {
+            vec!["(none)".to_string()]
+        } else {
+            names
+        };
+
+        for name in names {
+            groups.entry(name).or_default().push(task);
+        }
+    }
+
+    let mut entries: Vec<TimeEntry> = groups
+        .into_iter()
+        .map(|(name, tasks)| TimeEntry {
+            name,
+            spent: total_duration(tasks.iter().filter_map(|task| task.spent())),
+            estimate: total_estimate(tasks),
+        })
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.spent));
+
+    TimeSheet {
+        entries,
+        total: total_duration(tracked.iter().map(|(_, duration)| *duration)),
+        estimate: total_estimate(tracked.iter().map(|(task, _)| *task)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta};
+
+    use super::{TimeEntry, TimeSheet, time_report};
+    use crate::{cli::GroupBy, tasks::task::Task};
+
+    fn tasks() -> Vec<Task> {
+        [
+            "write the report +work +acme spent:1h30m est:2h",
+            "x 2024-06-03 2024-06-01 fix the build +work @laptop spent:45m est:30m",
+            "x 2024-05-20 2024-05-01 call the bank @phone spent:15m",
+            "not tracked +work",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+        .collect()
+    }
+
+    fn entry(name: &str, minutes: i64, estimate: Option<i64>) -> TimeEntry {
+        TimeEntry {
+            name: name.to_string(),
+            spent: TimeDelta::minutes(minutes),
+            estimate: estimate.map(TimeDelta::minutes),
+        }
+    }
+
+    #[test]
+    fn it_sums_the_time_per_task() {
+        assert_eq!(
+            time_report(&tasks(), None, None),
+            TimeSheet {
+                entries: vec![
+                    entry("write the report +work +acme", 90, Some(120)),
+                    entry("fix the build +work @laptop", 45, Some(30)),
+                    entry("call the bank @phone", 15, None),
+                ],
+                total: TimeDelta::minutes(150),
+                estimate: Some(TimeDelta::minutes(150)),
+            }
+        );
+    }
+
+    #[test]
+    fn it_sums_the_time_per_group() {
+        assert_eq!(
+            time_report(&tasks(), Some(GroupBy::Project), None).entries,
+            [
+                entry("+work", 135, Some(150)),
+                entry("+acme", 90, Some(120)),
+                entry("(none)", 15, None)
+            ]
+        );
+        assert_eq!(
+            time_report(&tasks(), Some(GroupBy::Context), None).entries,
+            [
+                entry("(none)", 90, Some(120)),
+                entry("@laptop", 45, Some(30)),
+                entry("@phone", 15, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_only_counts_the_tasks_completed_since_the_date() {
+        let since = NaiveDate::from_ymd_opt(2024, 6, 1);
+
+        let sheet = time_report(&tasks(), Some(GroupBy::Project), since);
+
+        assert_eq!(
+            sheet.entries,
+            [
+                entry("+work", 135, Some(150)),
+                entry("+acme", 90, Some(120))
+            ]
+        );
+        assert_eq!(sheet.total, TimeDelta::minutes(135));
+    }
+}
@@ -0,0 +1,484 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use chrono::{Local, NaiveDate, TimeDelta};
+
+use crate::{
+    git::GitHistory,
+    hooks::HookEvent,
+    storage::TaskStorage,
+    tasks::{
+        duration::{format_duration, total_duration},
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        query::TaskQuery,
+        task::{Task, TaskBuilder},
+    },
+};
+
+/// Changes applied by [`TodoRepository::modify`], `None` leaves the field untouched while
+/// `Some(None)` removes it.
+#[derive(Clone, Debug, Default)]
+pub struct TaskUpdate {
+    pub priority: Option<Option<char>>,
+    pub due_date: Option<Option<NaiveDate>>,
+    /// The number of the parent task, it's referred to by its `id:` tag
+    pub parent: Option<Option<usize>>,
+    /// The numbers of the tasks blocking these ones, empty to remove them
+    pub dependencies: Option<Vec<usize>>,
+}
+
+/// The operations of todors on a todo list, without any command line concern, so they can be
+/// used from other Rust code. Every mutation locks the storage, goes through its hooks, is
+/// recorded in the git history when there is one and returns the affected tasks as they were
+/// saved.
+pub struct TodoRepository {
+    storage: TaskStorage,
+    history: Option<GitHistory>,
+}
+
+impl TodoRepository {
+    pub fn new(storage: TaskStorage) -> Self {
+        Self {
+            storage,
+            history: None,
+        }
+    }
+
+    pub fn with_history(mut self, history: GitHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    fn record(&self, action: &str, tasks: &[Task]) {
+        if let Some(history) = &self.history {
+            history.record(action, tasks);
+        }
+    }
+
+    pub fn count(&self) -> Result<usize, TaskError> {
+        self.storage.count()
+    }
+
+    pub fn all(&self) -> Result<Vec<Task>, TaskError> {
+        self.storage.get_all()
+    }
+
+    /// The file to watch for changes made to the list by other programs.
+    pub fn watched_file(&self) -> Option<PathBuf> {
+        self.storage.watched_file()
+    }
+
+    /// The tasks matching `query`, or all of them without a query.
+    pub fn query(
+        &self,
+        query: Option<&TaskQuery>,
+        include_completed: bool,
+    ) -> Result<Vec<Task>, TaskError> {
+        self.storage.query(query, include_completed)
+    }
+
+    /// Add a task at the end of the list, without reading or rewriting the other tasks.
+    pub fn add(&self, text: &str, priority: Option<char>) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let task = TaskBuilder::new(self.storage.count()? + 1, text.to_string())
+            .priority(priority)
+            .created_at(Some(Local::now().date_naive()))
+            .build()?;
+
+        let added = self.storage.append_with_hooks(task)?;
+        self.record("add", &added);
+
+        Ok(added)
+    }
+
+    /// Append `imported` at the end of the list, they're numbered after the existing tasks.
+    pub fn import(&self, imported: Vec<Task>) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let mut tasks = self.storage.get_all()?;
+
+        let first_id = tasks.len() + 1;
+        for (idx, mut task) in imported.into_iter().enumerate() {
+            task.id = first_id + idx;
+            tasks.push(task);
+        }
+
+        let tasks = self.storage.persist_with_hooks(HookEvent::Add, tasks)?;
+        let imported: TaskList = tasks
+            .into_iter()
+            .filter(|task| task.id >= first_id)
+            .collect();
+        self.record("import", &imported);
+
+        Ok(imported)
+    }
+
+    pub fn complete(&self, query: &TaskQuery) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let before = self.storage.query(Some(query), true)?;
+        let mut tasks = before.clone();
+        tasks.iter_mut().for_each(|task| task.complete());
+
+        let completed = self
+            .storage
+            .update_with_hooks(HookEvent::Done, &before, tasks)?;
+        self.record("done", &completed);
+
+        Ok(completed)
+    }
+
+    pub fn uncomplete(&self, query: &TaskQuery) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let mut undone = self.storage.query(Some(query), true)?;
+        undone.iter_mut().for_each(|task| task.undo());
+
+        self.storage.update(&undone)?;
+        self.record("undone", &undone);
+
+        Ok(undone)
+    }
+
+    pub fn modify(&self, query: &TaskQuery, update: &TaskUpdate) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let mut before = self.storage.query(Some(query), true)?;
+        let mut tasks = before.clone();
+        let queried: HashSet<usize> = tasks.iter().map(|task| task.id).collect();
+
+        let linked_to_itself = update
+            .parent
+            .flatten()
+            .is_some_and(|id| queried.contains(&id))
+            || update
+                .dependencies
+                .as_ref()
+                .is_some_and(|deps| deps.iter().any(|id| queried.contains(id)));
+        if linked_to_itself {
+            return Err(TaskError::LinkedToItself);
+        }
+
+        // Links use the `id:` tags, they don't change when tasks are renumbered. Those given to
+        // the linked tasks are numbered after all the existing ones, the list is only read then.
+        let links = matches!(update.parent, Some(Some(_)))
+            || update
+                .dependencies
+                .as_ref()
+                .is_some_and(|deps| !deps.is_empty());
+        let mut all = if links {
+            self.storage.get_all()?
+        } else {
+            TaskList::new()
+        };
+        let unlinked = all.clone();
+
+        let parent = update
+            .parent
+            .map(|parent| parent.map(|id| all.stable_id_of(id)).transpose())
+            .transpose()?;
+        let dependencies = update
+            .dependencies
+            .as_ref()
+            .map(|deps| {
+                deps.iter()
+                    .map(|id| all.stable_id_of(*id))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        for (linked, old) in all.into_iter().zip(unlinked) {
+            if linked == old {
+                continue;
+            }
+            match tasks.iter_mut().find(|task| task.id == linked.id) {
+                Some(task) => *task = linked,
+                None => {
+                    before.push(old);
+                    tasks.push(linked);
+                }
+            }
+        }
+
+        for task in tasks.iter_mut().filter(|task| queried.contains(&task.id)) {
+            if let Some(priority) = update.priority {
+                task.priority = priority;
+            }
+
+            // A new due date doesn't keep the time of the old one
+            if let Some(due_date) = update.due_date {
+                task.due_date = due_date;
+                task.due_time = None;
+                task.tags.remove("at");
+            }
+
+            match &parent {
+                Some(Some(parent)) => {
+                    task.tags.insert("parent".to_string(), parent.clone());
+                }
+                Some(None) => {
+                    task.tags.remove("parent");
+                }
+                None => {}
+            }
+
+            match &dependencies {
+                Some(deps) if deps.is_empty() => {
+                    task.tags.remove("dep");
+                }
+                Some(deps) => {
+                    task.tags.insert("dep".to_string(), deps.join(","));
+                }
+                None => {}
+            }
+        }
+
+        let tasks = self
+            .storage
+            .update_with_hooks(HookEvent::Modify, &before, tasks)?;
+        let modified: TaskList = tasks
+            .into_iter()
+            .filter(|task| queried.contains(&task.id))
+            .collect();
+        self.record("modify", &modified);
+
+        Ok(modified)
+    }
+
+    pub fn remove(&self, query: &TaskQuery) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let removed = self.storage.query(Some(query), true)?;
+
+        self.storage.remove_with_hooks(&removed)?;
+        self.record("remove", &removed);
+
+        Ok(removed)
+    }
+
+    /// The task numbered `id`, given an `id:` tag first if it has none so it can still be found
+    /// once renumbered.
+    pub fn identify(&self, id: usize) -> Result<Task, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        // The new `id:` tag is numbered after all the existing ones
+        let mut tasks = self.storage.get_all()?;
+        let before = tasks.clone();
+        tasks.stable_id_of(id)?;
+
+        let identified: TaskList = tasks.iter().filter(|task| task.id == id).cloned().collect();
+        if tasks != before {
+            self.storage.update(&identified)?;
+            self.record("identify", &identified);
+        }
+
+        identified.into_iter().next().ok_or(TaskError::TaskNotFound)
+    }
+
+    /// Add `duration` to the `spent:` tag of the task with the `id:` tag `stable_id`.
+    pub fn add_spent(&self, stable_id: &str, duration: TimeDelta) -> Result<Task, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let before: TaskList = self
+            .storage
+            .get_all()?
+            .into_iter()
+            .filter(|task| task.stable_id() == Some(stable_id))
+            .take(1)
+            .collect();
+        let mut tasks = before.clone();
+        let Some(task) = tasks.first_mut() else {
+            return Err(TaskError::TaskNotFound);
+        };
+
+        let spent = total_duration([task.spent().unwrap_or_default(), duration]);
+        task.tags
+            .insert("spent".to_string(), format_duration(spent));
+
+        let tracked = self
+            .storage
+            .update_with_hooks(HookEvent::Modify, &before, tasks)?;
+        self.record("spent", &tracked);
+
+        tracked.into_iter().next().ok_or(TaskError::TaskNotFound)
+    }
+
+    /// Replace the tasks matching `query` with the ones `edit` returns for them, in order. Extra
+    /// tasks are appended at the end of the list and the matching tasks without a counterpart are
+    /// removed. The list stays locked while `edit` runs, e.g. in a text editor.
+    pub fn edit(
+        &self,
+        query: &TaskQuery,
+        edit: impl FnOnce(&TaskList) -> Result<TaskList, TaskError>,
+    ) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let tasks = self.storage.get_all()?;
+        let selected: TaskList = tasks.filter_from_query(query).collect();
+        if selected.is_empty() {
+            return Err(TaskError::TaskNotFound);
+        }
+
+        let edited = edit(&selected)?;
+        let ids: Vec<usize> = selected.iter().map(|task| task.id).collect();
+
+        self.storage
+            .persist_with_hooks(HookEvent::Modify, splice(tasks, &ids, edited.clone()))?;
+        self.record("edit", &edited);
+
+        Ok(edited)
+    }
+
+    /// Remove all the completed tasks.
+    pub fn clean(&self) -> Result<Vec<Task>, TaskError> {
+        let _lock = self.storage.lock()?;
+
+        let removed: TaskList = self
+            .storage
+            .get_all()?
+            .into_iter()
+            .filter(|task| task.completed)
+            .collect();
+
+        self.storage
+            .remove(&removed.iter().map(|task| task.id).collect::<Vec<_>>())?;
+        self.record("clean", &removed);
+
+        Ok(removed)
+    }
+}
+
+/// Replace the tasks identified by `ids` with the edited ones, in order. Extra edited tasks are
+/// appended at the end of the list and the selected tasks without a counterpart are removed.
+fn splice(tasks: TaskList, ids: &[usize], edited: TaskList) -> TaskList {
+    let mut edited = edited.into_iter();
+
+    let mut result: TaskList = tasks
+        .into_iter()
+        .filter_map(|task| {
+            if ids.contains(&task.id) {
+                edited.next()
+            } else {
+                Some(task)
+            }
+        })
+        .collect();
+    result.extend(edited);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{TaskUpdate, TodoRepository, splice};
+    use crate::{
+        storage::TaskStorage,
+        tasks::{error::TaskError, list::TaskList, query::TaskQuery, task::Task},
+    };
+
+    fn tasks(lines: &[&str]) -> TaskList {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+            .collect()
+    }
+
+    fn subjects(tasks: &TaskList) -> Vec<&str> {
+        tasks.iter().map(|task| task.subject.as_str()).collect()
+    }
+
+    #[test]
+    fn it_replaces_edited_tasks_in_place() {
+        let result = splice(tasks(&["one", "two", "three"]), &[2], tasks(&["deux"]));
+
+        assert_eq!(subjects(&result), vec!["one", "deux", "three"]);
+    }
+
+    #[test]
+    fn it_removes_deleted_lines() {
+        let result = splice(tasks(&["one", "two", "three"]), &[1, 3], tasks(&["un"]));
+
+        assert_eq!(subjects(&result), vec!["un", "two"]);
+    }
+
+    #[test]
+    fn it_appends_added_lines() {
+        let result = splice(tasks(&["one", "two"]), &[1], tasks(&["un", "new"]));
+
+        assert_eq!(subjects(&result), vec!["un", "two", "new"]);
+    }
+
+    #[test]
+    fn it_rejects_a_task_linked_to_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(&todo_file, "write the report\nsend the report\n").unwrap();
+        let repo = TodoRepository::new(TaskStorage::new(todo_file.clone()));
+        let query = TaskQuery {
+            indexes: vec![1, 2],
+            ..TaskQuery::default()
+        };
+
+        let as_parent = TaskUpdate {
+            parent: Some(Some(1)),
+            ..TaskUpdate::default()
+        };
+        let as_dependency = TaskUpdate {
+            dependencies: Some(vec![2]),
+            ..TaskUpdate::default()
+        };
+
+        assert!(matches!(
+            repo.modify(&query, &as_parent),
+            Err(TaskError::LinkedToItself)
+        ));
+        assert!(matches!(
+            repo.modify(&query, &as_dependency),
+            Err(TaskError::LinkedToItself)
+        ));
+        assert_eq!(
+            std::fs::read_to_string(todo_file).unwrap(),
+            "write the report\nsend the report\n"
+        );
+    }
+
+    #[test]
+    fn it_drops_the_due_time_with_a_new_due_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(
+            &todo_file,
+            "dentist due:2024-06-10T14:30\nmeeting due:2024-06-10 at:09:00\n",
+        )
+        .unwrap();
+        let repo = TodoRepository::new(TaskStorage::new(todo_file.clone()));
+        let query = TaskQuery {
+            indexes: vec![1, 2],
+            ..TaskQuery::default()
+        };
+
+        let moved = TaskUpdate {
+            due_date: NaiveDate::from_ymd_opt(2024, 6, 11).map(Some),
+            ..TaskUpdate::default()
+        };
+        repo.modify(&query, &moved).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&todo_file).unwrap(),
+            "dentist due:2024-06-11\nmeeting due:2024-06-11\n"
+        );
+
+        let removed = TaskUpdate {
+            due_date: Some(None),
+            ..TaskUpdate::default()
+        };
+        repo.modify(&query, &removed).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&todo_file).unwrap(),
+            "dentist\nmeeting\n"
+        );
+    }
+}
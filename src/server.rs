@@ -0,0 +1,333 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    repository::{TaskUpdate, TodoRepository},
+    tasks::{error::TaskError, query::TaskQuery, task::Task},
+};
+
+/// The body of `POST /tasks`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewTask {
+    text: String,
+    priority: Option<char>,
+}
+
+/// The body of `PATCH /tasks/<id>`, a missing field is left untouched while `null` removes it.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Changes {
+    #[serde(default, deserialize_with = "present")]
+    priority: Option<Option<char>>,
+    #[serde(default, deserialize_with = "present")]
+    due_date: Option<Option<NaiveDate>>,
+}
+
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// An error response, with its status code.
+#[derive(Debug)]
+struct ApiError(u16, String);
+
+impl From<TaskError> for ApiError {
+    fn from(err: TaskError) -> Self {
+        let status = match err {
+            TaskError::TaskNotFound => 404,
+            TaskError::FailedToParse
+            | TaskError::FailedToParseQuery
+            | TaskError::InvalidPriority => 400,
+            TaskError::HookRejected => 409,
+            _ => 500,
+        };
+
+        ApiError(status, err.to_string())
+    }
+}
+
+/// Serve the REST API until the process is killed. Requests are handled one at a time, with the
+/// same locking and hooks as the command line.
+pub fn serve(bind: &str, repo: TodoRepository, token: Option<&str>) -> Result<(), TaskError> {
+    let server = Server::http(bind).map_err(|err| {
+        eprintln!("Failed to listen on {bind}: {err}");
+        TaskError::FailedToRunServer
+    })?;
+    println!("Listening on http://{bind}");
+
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, json) = if !authorized(&request, token) {
+            (401, json!({ "error": "Missing or invalid token" }))
+        } else if request.as_reader().read_to_string(&mut body).is_err() {
+            (400, json!({ "error": "The body must be UTF-8" }))
+        } else {
+            match route(&repo, request.method(), request.url(), &body) {
+                Ok(response) => response,
+                Err(ApiError(status, error)) => (status, json!({ "error": error })),
+            }
+        };
+
+        let response = Response::from_string(json.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json")
+                    .expect("The header is valid"),
+            );
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to respond: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+fn authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && constant_time_eq(header.value.as_str(), &format!("Bearer {token}"))
+    })
+}
+
+/// Compare without leaking how many bytes of the token matched through the response time.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Decode a `application/x-www-form-urlencoded` value.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok());
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| decode(value))
+}
+
+fn by_id(id: &str) -> Result<TaskQuery, ApiError> {
+    let id = id
+        .parse()
+        .map_err(|_| ApiError(404, format!("Unknown task `{id}`")))?;
+
+    Ok(TaskQuery {
+        indexes: vec![id],
+        ..TaskQuery::default()
+    })
+}
+
+/// A priority is a letter from `A` to `Z`, anything else would end up in the subject.
+fn priority(priority: Option<char>) -> Result<Option<char>, ApiError> {
+    match priority {
+        Some(letter) if !letter.is_ascii_uppercase() => Err(TaskError::InvalidPriority.into()),
+        priority => Ok(priority),
+    }
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|err| ApiError(400, format!("Invalid body: {err}")))
+}
+
+/// The tasks changed by a request on a single task, 404 when it doesn't exist.
+fn found(tasks: Vec<Task>) -> Result<(u16, Value), ApiError> {
+    if tasks.is_empty() {
+        return Err(TaskError::TaskNotFound.into());
+    }
+
+    Ok((200, json!(tasks)))
+}
+
+/// Handle a request, returning the status code and the JSON body.
+///
+/// - `GET /tasks?q=<query>&all=true`: the tasks matching the query, with the completed ones
+///   when `all` is set
+/// - `POST /tasks` with `{"text": "...", "priority": "A"}`: add a task
+/// - `POST /tasks/<id>/done`: complete a task
+/// - `PATCH /tasks/<id>` with `{"priority": "A", "due_date": null}`: modify a task
+/// - `DELETE /tasks/<id>`: remove a task
+fn route(
+    repo: &TodoRepository,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> Result<(u16, Value), ApiError> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["tasks"]) => {
+            let task_query = query_param(query, "q")
+                .filter(|q| !q.trim().is_empty())
+                .map(|q| q.parse::<TaskQuery>())
+                .transpose()?;
+            let all = query_param(query, "all").is_some_and(|all| all != "false");
+
+            Ok((200, json!(repo.query(task_query.as_ref(), all)?)))
+        }
+        (Method::Post, ["tasks"]) => {
+            let new_task: NewTask = parse_body(body)?;
+
+            let priority = priority(new_task.priority)?;
+
+            Ok((201, json!(repo.add(&new_task.text, priority)?)))
+        }
+        (Method::Post, ["tasks", id, "done"]) => found(repo.complete(&by_id(id)?)?),
+        (Method::Patch, ["tasks", id]) => {
+            let query = by_id(id)?;
+            let changes: Changes = parse_body(body)?;
+            let update = TaskUpdate {
+                priority: changes.priority.map(priority).transpose()?,
+                due_date: changes.due_date,
+                ..TaskUpdate::default()
+            };
+
+            found(repo.modify(&query, &update)?)
+        }
+        (Method::Delete, ["tasks", id]) => found(repo.remove(&by_id(id)?)?),
+        _ => Err(ApiError(404, format!("No route for {method} {path}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+    use tiny_http::Method;
+
+    use super::{ApiError, constant_time_eq, decode, route};
+    use crate::{repository::TodoRepository, storage::TaskStorage};
+
+    fn repo() -> (tempfile::TempDir, TodoRepository) {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(
+            &todo_file,
+            "2024-05-01 call mom +family\n2024-05-01 buy milk\n",
+        )
+        .unwrap();
+
+        (dir, TodoRepository::new(TaskStorage::new(todo_file)))
+    }
+
+    fn subjects(json: &Value) -> Vec<&str> {
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["subject"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_lists_tasks_matching_a_query() {
+        let (_dir, repo) = repo();
+
+        let (status, json) = route(&repo, &Method::Get, "/tasks?q=%2Bfamily", "").unwrap();
+
+        assert_eq!(status, 200);
+        assert_eq!(subjects(&json), vec!["call mom +family"]);
+        assert_eq!(json[0]["id"], 1);
+        assert_eq!(json[0]["projects"], json!(["family"]));
+    }
+
+    #[test]
+    fn it_adds_completes_modifies_and_deletes_tasks() {
+        let (_dir, repo) = repo();
+
+        let (status, json) = route(
+            &repo,
+            &Method::Post,
+            "/tasks",
+            r#"{"text": "write report", "priority": "A"}"#,
+        )
+        .unwrap();
+        assert_eq!(status, 201);
+        assert_eq!(json[0]["id"], 3);
+
+        let (_, json) = route(&repo, &Method::Post, "/tasks/3/done", "").unwrap();
+        assert_eq!(json[0]["completed"], true);
+
+        let (_, json) = route(
+            &repo,
+            &Method::Patch,
+            "/tasks/2",
+            r#"{"priority": "B", "due_date": "2024-06-10"}"#,
+        )
+        .unwrap();
+        assert_eq!(json[0]["priority"], "B");
+        assert_eq!(json[0]["due_date"], "2024-06-10");
+
+        route(&repo, &Method::Delete, "/tasks/1", "").unwrap();
+        let (_, json) = route(&repo, &Method::Get, "/tasks?all=true", "").unwrap();
+        assert_eq!(subjects(&json), vec!["buy milk", "write report"]);
+    }
+
+    #[test]
+    fn it_reports_errors_with_a_status() {
+        let (_dir, repo) = repo();
+
+        let status = |method: Method, url: &str, body: &str| match route(&repo, &method, url, body)
+        {
+            Ok((status, _)) => status,
+            Err(ApiError(status, _)) => status,
+        };
+
+        assert_eq!(status(Method::Delete, "/tasks/42", ""), 404);
+        assert_eq!(status(Method::Post, "/tasks", "{}"), 400);
+        assert_eq!(status(Method::Put, "/tasks", ""), 404);
+        assert_eq!(
+            status(Method::Post, "/tasks", r#"{"text": "a", "priority": "1"}"#),
+            400
+        );
+        assert_eq!(
+            status(Method::Patch, "/tasks/1", r#"{"priority": "b"}"#),
+            400
+        );
+    }
+
+    #[test]
+    fn it_decodes_query_values() {
+        assert_eq!(decode("%2Bwork+%40office%zz"), "+work @office%zz");
+        assert!(constant_time_eq("Bearer secret", "Bearer secret"));
+        assert!(!constant_time_eq("Bearer secreT", "Bearer secret"));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    fs::{File, OpenOptions},
+    path::Path,
+};
+
+use crate::tasks::error::TaskError;
+
+/// An exclusive lock on a file next to the storage, held until dropped, so concurrent commands
+/// can't overwrite each other's changes.
+pub struct StorageLock {
+    _file: File,
+}
+
+impl StorageLock {
+    /// Wait for the lock on `path`, creating the file if needed. The file is left behind on
+    /// release, removing it would let another process lock a file that's about to be deleted.
+    pub fn acquire(path: &Path) -> Result<Self, TaskError> {
+        if let Some(parent) = path.parent()
+            && std::fs::create_dir_all(parent).is_err()
+        {
+            return Err(TaskError::FailedToLock);
+        }
+
+        let Ok(file) = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+        else {
+            return Err(TaskError::FailedToLock);
+        };
+
+        if let Err(err) = file.lock() {
+            eprintln!("Failed to lock {}: {err}", path.display());
+            return Err(TaskError::FailedToLock);
+        }
+
+        Ok(Self { _file: file })
+    }
+}
@@ -1,52 +1,321 @@
-use std::{fs::OpenOptions, io::Write, path::PathBuf};
+mod lock;
+mod sqlite;
+mod todotxt;
 
-use crate::tasks::{error::TaskError, list::TaskList, task::Task};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{Config, StorageBackend},
+    hooks::{self, Change, HookEvent, Hooks},
+    tasks::{
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        query::TaskQuery,
+        task::Task,
+    },
+};
+
+pub use lock::StorageLock;
+pub use sqlite::SqliteBackend;
+pub use todotxt::TodoTxtBackend;
+
+/// Where the tasks are stored. Only reading and writing the whole list is required, backends that
+/// can do better, like indexed queries, override the other methods.
+pub trait Backend {
+    fn get_all(&self) -> Result<TaskList, TaskError>;
+
+    fn persist(&self, tasks: TaskList) -> Result<(), TaskError>;
+
+    fn query(
+        &self,
+        query: Option<&TaskQuery>,
+        include_completed: bool,
+    ) -> Result<TaskList, TaskError> {
+        let mut tasks = self.get_all()?;
+
+        if !include_completed {
+            tasks.retain(|task| !task.completed);
+        }
+
+        if let Some(query) = query {
+            tasks = tasks.filter_from_query(query).collect();
+        }
+
+        Ok(tasks)
+    }
+
+    fn count(&self) -> Result<usize, TaskError> {
+        Ok(self.get_all()?.len())
+    }
+
+    /// Add `task` at the end of the list, it must already be numbered after the existing tasks.
+    fn append(&self, task: Task) -> Result<(), TaskError> {
+        let mut tasks = self.get_all()?;
+        tasks.push(task);
+
+        self.persist(tasks)
+    }
+
+    /// Save the new version of `tasks`, each replaces the task with the same number.
+    fn update(&self, tasks: &[Task]) -> Result<(), TaskError> {
+        let mut all = self.get_all()?;
+        for task in tasks {
+            if let Some(existing) = all.iter_mut().find(|existing| existing.id == task.id) {
+                *existing = task.clone();
+            }
+        }
+
+        self.persist(all)
+    }
+
+    /// Remove the tasks numbered `ids`, the following ones are numbered again without a gap.
+    fn remove(&self, ids: &[usize]) -> Result<(), TaskError> {
+        let mut all = self.get_all()?;
+        all.retain(|task| !ids.contains(&task.id));
+
+        self.persist(all)
+    }
+
+    /// The file locked while the list is changed, backends without one aren't locked.
+    fn lock_file(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// The file changed when the tasks are, watched to refresh what's displayed.
+    fn watched_file(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// The hidden `.<name>.lock` file next to `path`.
+fn lock_file_for(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+
+    Some(path.with_file_name(format!(".{name}.lock")))
+}
 
 pub struct TaskStorage {
-    todo_file: PathBuf,
+    backend: Box<dyn Backend>,
+    hooks: Option<Hooks>,
 }
 
 impl TaskStorage {
     pub fn new(todo_file: PathBuf) -> Self {
-        Self { todo_file }
+        Self::with_backend(Box::new(TodoTxtBackend::new(todo_file)))
+    }
+
+    pub fn with_backend(backend: Box<dyn Backend>) -> Self {
+        Self {
+            backend,
+            hooks: None,
+        }
+    }
+
+    /// The storage of the backend selected in the config.
+    pub fn from_config(config: &Config) -> Result<Self, TaskError> {
+        let backend: Box<dyn Backend> = match config.backend {
+            StorageBackend::TodoTxt => Box::new(TodoTxtBackend::new(config.todo_file())),
+            StorageBackend::Sqlite => Box::new(SqliteBackend::open(&config.database_file())?),
+        };
+
+        Ok(Self::with_backend(backend))
+    }
+
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = Some(hooks);
+        self
     }
 }
 
 impl TaskStorage {
     pub fn get_all(&self) -> Result<TaskList, TaskError> {
-        let Ok(content) = std::fs::read_to_string(&self.todo_file) else {
-            return Err(TaskError::FailedToOpenTodoFile);
+        self.backend.get_all()
+    }
+
+    pub fn query(
+        &self,
+        query: Option<&TaskQuery>,
+        include_completed: bool,
+    ) -> Result<TaskList, TaskError> {
+        self.backend.query(query, include_completed)
+    }
+
+    pub fn count(&self) -> Result<usize, TaskError> {
+        self.backend.count()
+    }
+
+    pub fn persist(&self, tasks: TaskList) -> Result<(), TaskError> {
+        self.backend.persist(tasks)
+    }
+
+    pub fn update(&self, tasks: &[Task]) -> Result<(), TaskError> {
+        self.backend.update(tasks)
+    }
+
+    pub fn remove(&self, ids: &[usize]) -> Result<(), TaskError> {
+        self.backend.remove(ids)
+    }
+
+    /// Lock the list until the returned guard is dropped. Take it before reading tasks that'll be
+    /// changed so no other command can write in between.
+    pub fn lock(&self) -> Result<Option<StorageLock>, TaskError> {
+        self.backend
+            .lock_file()
+            .map(|path| StorageLock::acquire(&path))
+            .transpose()
+    }
+
+    pub fn watched_file(&self) -> Option<PathBuf> {
+        self.backend.watched_file()
+    }
+
+    /// Persist the tasks changed by `event`, running the pre hooks on the changes first and the
+    /// post hooks once they're saved. Returns the tasks as they were saved.
+    pub fn persist_with_hooks(
+        &self,
+        event: HookEvent,
+        mut tasks: TaskList,
+    ) -> Result<TaskList, TaskError> {
+        let Some(hooks) = self.hooks.as_ref().filter(|hooks| hooks.has_hooks(event)) else {
+            self.persist(tasks.clone())?;
+            return Ok(tasks);
         };
 
-        let mut tasks = TaskList::new();
-        for (idx, line) in content.lines().enumerate() {
-            let task = Task::from_str(idx + 1, line)?;
+        let changes = hooks::diff(&self.get_all()?, &tasks);
+        let changes = hooks.run_pre(event, changes)?;
+        hooks::apply(&mut tasks, &changes)?;
 
-            tasks.push(task)
-        }
+        self.persist(tasks.clone())?;
+        hooks.run_post(event, &changes);
 
         Ok(tasks)
     }
 
-    pub fn persist(&self, tasks: TaskList) -> Result<(), TaskError> {
-        let mut file = if let Ok(file) = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(&self.todo_file)
+    /// Save `tasks`, the changed versions of `before`, without rewriting the rest of the list
+    /// when the backend can. Falls back to a full rewrite when a pre hook also adds, removes or
+    /// changes other tasks. Returns the tasks as they were saved.
+    pub fn update_with_hooks(
+        &self,
+        event: HookEvent,
+        before: &TaskList,
+        mut tasks: TaskList,
+    ) -> Result<TaskList, TaskError> {
+        let Some(hooks) = self.hooks.as_ref().filter(|hooks| hooks.has_hooks(event)) else {
+            self.update(&tasks)?;
+            return Ok(tasks);
+        };
+
+        let changes = hooks::diff(before, &tasks);
+        let changes = hooks.run_pre(event, changes)?;
+
+        let ids: HashSet<usize> = tasks.iter().map(|task| task.id).collect();
+        if changes
+            .iter()
+            .all(|change| ids.contains(&change.id) && change.new.is_some())
         {
-            file
+            hooks::apply(&mut tasks, &changes)?;
+            self.update(&tasks)?;
         } else {
-            return Err(TaskError::FailedToOpenTodoFile);
+            let mut all = self.get_all()?;
+            for task in &tasks {
+                if let Some(existing) = all.iter_mut().find(|existing| existing.id == task.id) {
+                    *existing = task.clone();
+                }
+            }
+            hooks::apply(&mut all, &changes)?;
+
+            self.persist(all.clone())?;
+            tasks = all
+                .into_iter()
+                .filter(|task| ids.contains(&task.id))
+                .collect();
+        }
+        hooks.run_post(event, &changes);
+
+        Ok(tasks)
+    }
+
+    /// Remove `removed` without rewriting the rest of the list when the backend can, running the
+    /// remove hooks on them. Falls back to a full rewrite when a pre hook keeps or changes a task
+    /// instead.
+    pub fn remove_with_hooks(&self, removed: &TaskList) -> Result<(), TaskError> {
+        let mut ids: Vec<usize> = removed.iter().map(|task| task.id).collect();
+        let Some(hooks) = self
+            .hooks
+            .as_ref()
+            .filter(|hooks| hooks.has_hooks(HookEvent::Remove))
+        else {
+            return self.backend.remove(&ids);
         };
 
-        for task in tasks {
-            match file.write_fmt(format_args!("{task}\n")) {
-                Ok(_) => {}
-                Err(_) => return Err(TaskError::FailedToSave),
-            }
+        let changes = hooks::diff(removed, &TaskList::new());
+        let changes = hooks.run_pre(HookEvent::Remove, changes)?;
+
+        if changes.iter().all(|change| change.new.is_none()) {
+            ids.extend(changes.iter().map(|change| change.id));
+            self.backend.remove(&ids)?;
+        } else {
+            // The tasks the hook printed back stay in place
+            let listed: HashSet<usize> = changes.iter().map(|change| change.id).collect();
+            let mut all = self.get_all()?;
+            all.retain(|task| !ids.contains(&task.id) || listed.contains(&task.id));
+            hooks::apply(&mut all, &changes)?;
+
+            self.persist(all)?;
         }
+        hooks.run_post(HookEvent::Remove, &changes);
 
         Ok(())
     }
+
+    /// Append `task` without rewriting the list, running the add hooks on it. Falls back to a
+    /// full rewrite when a pre hook also changes other tasks. Returns the tasks as they were
+    /// saved, empty if a hook dropped the new task.
+    pub fn append_with_hooks(&self, task: Task) -> Result<TaskList, TaskError> {
+        let Some(hooks) = self
+            .hooks
+            .as_ref()
+            .filter(|hooks| hooks.has_hooks(HookEvent::Add))
+        else {
+            self.backend.append(task.clone())?;
+            return Ok(vec![task]);
+        };
+
+        let id = task.id;
+        let changes = vec![Change {
+            id,
+            old: None,
+            new: Some(task.to_string()),
+        }];
+        let changes = hooks.run_pre(HookEvent::Add, changes)?;
+
+        // Like `hooks::apply`, the hook output only overrides the changes it lists
+        let added = if changes.iter().all(|change| change.id == id) {
+            let task = match changes.last() {
+                None => Some(task),
+                Some(Change {
+                    new: Some(line), ..
+                }) => Some(Task::from_str(id, line)?),
+                Some(Change { new: None, .. }) => None,
+            };
+            if let Some(task) = &task {
+                self.backend.append(task.clone())?;
+            }
+
+            task.into_iter().collect()
+        } else {
+            let mut tasks = self.get_all()?;
+            tasks.push(task);
+            hooks::apply(&mut tasks, &changes)?;
+
+            self.persist(tasks.clone())?;
+            tasks.into_iter().filter(|task| task.id == id).collect()
+        };
+        hooks.run_post(HookEvent::Add, &changes);
+
+        Ok(added)
+    }
 }
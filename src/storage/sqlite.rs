@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, params, params_from_iter, types::Value};
+
+use super::{Backend, lock_file_for};
+use crate::tasks::{error::TaskError, list::TaskList, query::TaskQuery, task::Task};
+
+// The todo.txt line stays the source of truth, the other columns and the terms table only exist
+// to answer queries with indexes instead of parsing every task.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS tasks (
+    id INTEGER PRIMARY KEY,
+    line TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    completed INTEGER NOT NULL,
+    due_date TEXT
+);
+CREATE INDEX IF NOT EXISTS tasks_completed ON tasks (completed);
+CREATE INDEX IF NOT EXISTS tasks_due_date ON tasks (due_date);
+CREATE TABLE IF NOT EXISTS task_terms (
+    task_id INTEGER NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS task_terms_lookup ON task_terms (kind, value);
+";
+
+/// A SQLite database, better suited than todo.txt for lists with a large archive of completed
+/// tasks.
+pub struct SqliteBackend {
+    conn: Connection,
+    path: PathBuf,
+}
+
+fn database_error(err: rusqlite::Error) -> TaskError {
+    eprintln!("Database error: {err}");
+    TaskError::DatabaseError
+}
+
+impl SqliteBackend {
+    pub fn open(path: &Path) -> Result<Self, TaskError> {
+        if let Some(parent) = path.parent()
+            && std::fs::create_dir_all(parent).is_err()
+        {
+            return Err(TaskError::DatabaseError);
+        }
+
+        let conn = Connection::open(path).map_err(database_error)?;
+        conn.execute_batch(SCHEMA).map_err(database_error)?;
+
+        Ok(Self {
+            conn,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn select(&self, filter: &str, values: Vec<Value>) -> Result<TaskList, TaskError> {
+        let sql = format!("SELECT id, line FROM tasks WHERE {filter} ORDER BY id");
+        let mut stmt = self.conn.prepare(&sql).map_err(database_error)?;
+
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(database_error)?;
+
+        let mut tasks = TaskList::new();
+        for row in rows {
+            let (id, line) = row.map_err(database_error)?;
+            tasks.push(Task::from_str(id as usize, &line)?);
+        }
+
+        Ok(tasks)
+    }
+}
+
+impl Backend for SqliteBackend {
+    fn get_all(&self) -> Result<TaskList, TaskError> {
+        self.select("1", Vec::new())
+    }
+
+    fn persist(&self, tasks: TaskList) -> Result<(), TaskError> {
+        let tx = self.conn.unchecked_transaction().map_err(database_error)?;
+
+        tx.execute_batch("DELETE FROM task_terms; DELETE FROM tasks;")
+            .map_err(database_error)?;
+
+        // Like the lines of todo.txt, ids are the position of the task in the list
+        for (idx, task) in tasks.iter().enumerate() {
+            insert(&tx, idx + 1, task)?;
+        }
+
+        tx.commit().map_err(database_error)
+    }
+
+    fn append(&self, task: Task) -> Result<(), TaskError> {
+        let tx = self.conn.unchecked_transaction().map_err(database_error)?;
+        insert(&tx, task.id, &task)?;
+
+        tx.commit().map_err(database_error)
+    }
+
+    fn update(&self, tasks: &[Task]) -> Result<(), TaskError> {
+        let tx = self.conn.unchecked_transaction().map_err(database_error)?;
+
+        for task in tasks {
+            write(
+                &tx,
+                "UPDATE tasks SET line = ?2, subject = ?3, completed = ?4, due_date = ?5 WHERE id = ?1",
+                task.id,
+                task,
+            )?;
+        }
+
+        tx.commit().map_err(database_error)
+    }
+
+    fn remove(&self, ids: &[usize]) -> Result<(), TaskError> {
+        let tx = self.conn.unchecked_transaction().map_err(database_error)?;
+
+        tx.execute_batch(
+            "CREATE TEMP TABLE IF NOT EXISTS removed_tasks (id INTEGER PRIMARY KEY);
+             DELETE FROM removed_tasks;",
+        )
+        .map_err(database_error)?;
+        {
+            let mut insert_removed = tx
+                .prepare("INSERT OR IGNORE INTO removed_tasks (id) VALUES (?1)")
+                .map_err(database_error)?;
+            for id in ids {
+                insert_removed
+                    .execute([*id as i64])
+                    .map_err(database_error)?;
+            }
+        }
+
+        // Like the lines of todo.txt, the following tasks move up. Their ids are negated first so
+        // they stay unique at every step of the update, and the terms only refer to them again
+        // once it's done.
+        tx.execute_batch(
+            "PRAGMA defer_foreign_keys = ON;
+             DELETE FROM task_terms WHERE task_id IN (SELECT id FROM removed_tasks);
+             DELETE FROM tasks WHERE id IN (SELECT id FROM removed_tasks);
+             UPDATE tasks
+                SET id = -(id - (SELECT COUNT(*) FROM removed_tasks WHERE removed_tasks.id < tasks.id))
+              WHERE id > (SELECT MIN(id) FROM removed_tasks);
+             UPDATE tasks SET id = -id WHERE id < 0;
+             UPDATE task_terms
+                SET task_id = task_id - (SELECT COUNT(*) FROM removed_tasks WHERE removed_tasks.id < task_terms.task_id)
+              WHERE task_id > (SELECT MIN(id) FROM removed_tasks);
+             DELETE FROM removed_tasks;",
+        )
+        .map_err(database_error)?;
+
+        tx.commit().map_err(database_error)
+    }
+
+    fn lock_file(&self) -> Option<PathBuf> {
+        lock_file_for(&self.path)
+    }
+
+    fn watched_file(&self) -> Option<PathBuf> {
+        Some(self.path.clone())
+    }
+
+    fn query(
+        &self,
+        query: Option<&TaskQuery>,
+        include_completed: bool,
+    ) -> Result<TaskList, TaskError> {
+        let mut values = Vec::new();
+
+        // Same semantic as `filter_from_query`: a task matches if any of the terms matches
+        let filter = match query {
+            None => "1".to_string(),
+            Some(query) => {
+                let mut conditions = Vec::new();
+
+                if !query.indexes.is_empty() {
+                    conditions.push(format!("id IN ({})", placeholders(query.indexes.len())));
+                    values.extend(query.indexes.iter().map(|idx| Value::Integer(*idx as i64)));
+                }
+
+                let terms = [
+                    ("project", &query.projects),
+                    ("context", &query.contexts),
+                    ("hashtag", &query.hashtags),
+                ];
+                for (kind, terms) in terms.into_iter().filter(|(_, terms)| !terms.is_empty()) {
+                    conditions.push(format!(
+                        "id IN (SELECT task_id FROM task_terms WHERE kind = ? AND value IN ({}))",
+                        placeholders(terms.len())
+                    ));
+                    values.push(Value::Text(kind.to_string()));
+                    values.extend(terms.iter().cloned().map(Value::Text));
+                }
+
+                if let Some(due_date) = query.due_date {
+                    conditions.push("due_date = ?".to_string());
+                    values.push(Value::Text(due_date.to_string()));
+                }
+
+                if !query.subject.is_empty() {
+                    conditions.push("instr(subject, ?) > 0".to_string());
+                    values.push(Value::Text(query.subject.clone()));
+                }
+
+                if conditions.is_empty() {
+                    "0".to_string()
+                } else {
+                    conditions.join(" OR ")
+                }
+            }
+        };
+
+        let completed = if include_completed {
+            "1"
+        } else {
+            "completed = 0"
+        };
+
+        self.select(&format!("{completed} AND ({filter})"), values)
+    }
+
+    fn count(&self) -> Result<usize, TaskError> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(database_error)
+    }
+}
+
+fn insert(conn: &Connection, id: usize, task: &Task) -> Result<(), TaskError> {
+    write(
+        conn,
+        "INSERT INTO tasks (id, line, subject, completed, due_date) VALUES (?1, ?2, ?3, ?4, ?5)",
+        id,
+        task,
+    )
+}
+
+/// Run `sql`, an insert or an update of the row of the task, and index its terms again.
+fn write(conn: &Connection, sql: &str, id: usize, task: &Task) -> Result<(), TaskError> {
+    // Index the task as it'll be read back from its line
+    let line = task.to_string();
+    let task = Task::from_str(id, &line)?;
+
+    conn.prepare_cached(sql)
+        .and_then(|mut stmt| {
+            stmt.execute(params![
+                id as i64,
+                line,
+                task.subject,
+                task.completed,
+                task.due_date.map(|date| date.to_string()),
+            ])
+        })
+        .map_err(database_error)?;
+
+    conn.prepare_cached("DELETE FROM task_terms WHERE task_id = ?1")
+        .and_then(|mut stmt| stmt.execute([id as i64]))
+        .map_err(database_error)?;
+
+    let mut insert_term = conn
+        .prepare_cached("INSERT INTO task_terms (task_id, kind, value) VALUES (?1, ?2, ?3)")
+        .map_err(database_error)?;
+    let terms = [
+        ("project", &task.projects),
+        ("context", &task.contexts),
+        ("hashtag", &task.hashtags),
+    ];
+    for (kind, values) in terms {
+        for value in values {
+            insert_term
+                .execute(params![id as i64, kind, value])
+                .map_err(database_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteBackend;
+    use crate::storage::{Backend, TodoTxtBackend};
+    use crate::tasks::{query::TaskQuery, task::Task};
+
+    const TASKS: &str = "(A) call mom @phone +family
+x 2024-06-02 2024-05-01 write report +work due:2024-06-01
+review the pull request +work #next team:devops
+buy milk @store
+plan holidays +family due:2024-06-01 #backlog
+";
+
+    fn backends() -> (tempfile::TempDir, TodoTxtBackend, SqliteBackend) {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(&todo_file, TASKS).unwrap();
+
+        // Both backends store the formatted tasks, write them the same way before comparing
+        let todo_txt = TodoTxtBackend::new(todo_file);
+        let tasks = todo_txt.get_all().unwrap();
+        todo_txt.persist(tasks.clone()).unwrap();
+
+        let sqlite = SqliteBackend::open(&dir.path().join("todo.db")).unwrap();
+        sqlite.persist(tasks).unwrap();
+
+        (dir, todo_txt, sqlite)
+    }
+
+    #[test]
+    fn it_round_trips_tasks() {
+        let (_dir, todo_txt, sqlite) = backends();
+
+        assert_eq!(sqlite.get_all().unwrap(), todo_txt.get_all().unwrap());
+        assert_eq!(sqlite.count().unwrap(), 5);
+    }
+
+    #[test]
+    fn it_appends_a_task() {
+        let (_dir, _todo_txt, sqlite) = backends();
+
+        let task = Task::from_str(6, "(B) new task +work").unwrap();
+        sqlite.append(task.clone()).unwrap();
+
+        assert_eq!(sqlite.count().unwrap(), 6);
+        let query = "+work".parse::<TaskQuery>().unwrap();
+        assert_eq!(
+            sqlite.query(Some(&query), false).unwrap().last(),
+            Some(&task)
+        );
+    }
+
+    #[test]
+    fn it_queries_like_the_todo_txt_backend() {
+        let (_dir, todo_txt, sqlite) = backends();
+
+        let queries = [
+            "+work",
+            "@phone @store",
+            "#next",
+            "1,4",
+            "due:2024-06-01",
+            "milk",
+            "+family 4",
+            "",
+        ];
+        for query in queries {
+            let query = query.parse::<TaskQuery>().unwrap();
+            for include_completed in [true, false] {
+                assert_eq!(
+                    sqlite.query(Some(&query), include_completed).unwrap(),
+                    todo_txt.query(Some(&query), include_completed).unwrap(),
+                    "{query:?} {include_completed}"
+                );
+            }
+        }
+
+        assert_eq!(
+            sqlite.query(None, false).unwrap(),
+            todo_txt.query(None, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_updates_and_removes_tasks_like_the_todo_txt_backend() {
+        let (_dir, todo_txt, sqlite) = backends();
+
+        let mut task = todo_txt.get_all().unwrap().remove(2);
+        task.complete();
+        for backend in [&todo_txt as &dyn Backend, &sqlite] {
+            backend.update(std::slice::from_ref(&task)).unwrap();
+            backend.remove(&[1, 4]).unwrap();
+        }
+
+        assert_eq!(sqlite.get_all().unwrap(), todo_txt.get_all().unwrap());
+        assert_eq!(sqlite.count().unwrap(), 3);
+
+        // The terms follow their tasks once renumbered
+        let query = "+family #next".parse::<TaskQuery>().unwrap();
+        assert_eq!(
+            sqlite.query(Some(&query), true).unwrap(),
+            todo_txt.query(Some(&query), true).unwrap()
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use super::{Backend, lock_file_for};
+use crate::tasks::{error::TaskError, list::TaskList, task::Task};
+
+/// The todo.txt file, one task per line.
+pub struct TodoTxtBackend {
+    todo_file: PathBuf,
+}
+
+impl TodoTxtBackend {
+    pub fn new(todo_file: PathBuf) -> Self {
+        Self { todo_file }
+    }
+
+    fn create_todo_dir(&self) -> Result<(), TaskError> {
+        if let Some(todo_dir) = self.todo_file.parent()
+            && std::fs::create_dir_all(todo_dir).is_err()
+        {
+            return Err(TaskError::FailedToOpenTodoFile);
+        }
+
+        Ok(())
+    }
+}
+
+fn ends_with_newline(file: &mut File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+
+    let mut last = [0; 1];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+
+    Ok(last[0] == b'\n')
+}
+
+impl Backend for TodoTxtBackend {
+    fn get_all(&self) -> Result<TaskList, TaskError> {
+        // A missing todo file is just an empty list, it'll be created on the first write
+        let content = match std::fs::read_to_string(&self.todo_file) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(_) => return Err(TaskError::FailedToOpenTodoFile),
+        };
+
+        let mut tasks = TaskList::new();
+        for (idx, line) in content.lines().enumerate() {
+            let task = Task::from_str(idx + 1, line)?;
+
+            tasks.push(task)
+        }
+
+        Ok(tasks)
+    }
+
+    fn persist(&self, tasks: TaskList) -> Result<(), TaskError> {
+        self.create_todo_dir()?;
+
+        let mut file = if let Ok(file) = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&self.todo_file)
+        {
+            file
+        } else {
+            return Err(TaskError::FailedToOpenTodoFile);
+        };
+
+        for task in tasks {
+            match file.write_fmt(format_args!("{task}\n")) {
+                Ok(_) => {}
+                Err(_) => return Err(TaskError::FailedToSave),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn count(&self) -> Result<usize, TaskError> {
+        // Lines are counted the same way as `get_all`, without parsing them
+        match std::fs::read_to_string(&self.todo_file) {
+            Ok(content) => Ok(content.lines().count()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(_) => Err(TaskError::FailedToOpenTodoFile),
+        }
+    }
+
+    fn append(&self, task: Task) -> Result<(), TaskError> {
+        self.create_todo_dir()?;
+
+        let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.todo_file)
+        else {
+            return Err(TaskError::FailedToOpenTodoFile);
+        };
+
+        // Without a trailing newline the task would be glued to the last one
+        let separator = match ends_with_newline(&mut file) {
+            Ok(true) => "",
+            Ok(false) => "\n",
+            Err(_) => return Err(TaskError::FailedToOpenTodoFile),
+        };
+
+        match writeln!(file, "{separator}{task}") {
+            Ok(_) => Ok(()),
+            Err(_) => Err(TaskError::FailedToSave),
+        }
+    }
+
+    fn lock_file(&self) -> Option<PathBuf> {
+        lock_file_for(&self.todo_file)
+    }
+
+    fn watched_file(&self) -> Option<PathBuf> {
+        Some(self.todo_file.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TodoTxtBackend;
+    use crate::storage::Backend;
+    use crate::tasks::task::Task;
+
+    fn append_to(content: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        std::fs::write(&todo_file, content).unwrap();
+
+        let backend = TodoTxtBackend::new(todo_file.clone());
+        let id = backend.count().unwrap() + 1;
+        backend
+            .append(Task::from_str(id, "new task").unwrap())
+            .unwrap();
+
+        std::fs::read_to_string(todo_file).unwrap()
+    }
+
+    #[test]
+    fn it_appends_a_line() {
+        assert_eq!(append_to("first\n"), "first\nnew task\n");
+    }
+
+    #[test]
+    fn it_appends_after_a_missing_trailing_newline() {
+        assert_eq!(append_to("first\nsecond"), "first\nsecond\nnew task\n");
+    }
+
+    #[test]
+    fn it_appends_to_an_empty_file() {
+        assert_eq!(append_to(""), "new task\n");
+    }
+
+    #[test]
+    fn it_counts_lines_like_get_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let todo_file = dir.path().join("todo.txt");
+        let backend = TodoTxtBackend::new(todo_file.clone());
+        assert_eq!(backend.count().unwrap(), 0);
+
+        std::fs::write(&todo_file, "first\r\nsecond").unwrap();
+        assert_eq!(backend.count().unwrap(), backend.get_all().unwrap().len());
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::{HashMap, HashSet};
+
+use super::task::Task;
+
+/// The links between tasks made by their `dep:` tags. Only open tasks block others, a task is no
+/// longer blocked once its dependencies are completed or removed. Tasks depending on each other,
+/// directly or not, can't be done first: the links of such a cycle block neither task.
+#[derive(Debug, Default)]
+pub struct Dependencies {
+    open: HashSet<String>,
+    blocking: HashSet<String>,
+    cyclic: HashMap<String, HashSet<String>>,
+}
+
+impl Dependencies {
+    /// Build it from the whole list: a query may leave out the tasks blocking the queried ones.
+    pub fn new(tasks: &[Task]) -> Self {
+        let open: HashSet<String> = tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter_map(|task| task.stable_id().map(str::to_string))
+            .collect();
+
+        let links: HashMap<&str, Vec<&str>> = tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter_map(|task| {
+                let deps = task.dependencies().filter(|dep| open.contains(*dep));
+                Some((task.stable_id()?, deps.collect()))
+            })
+            .collect();
+
+        let mut cyclic: HashMap<String, HashSet<String>> = HashMap::new();
+        for (id, deps) in &links {
+            for dep in deps.iter().filter(|dep| reaches(&links, dep, id)) {
+                cyclic
+                    .entry(id.to_string())
+                    .or_default()
+                    .insert(dep.to_string());
+            }
+        }
+
+        let mut dependencies = Self {
+            open,
+            blocking: HashSet::new(),
+            cyclic,
+        };
+        dependencies.blocking = tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .flat_map(|task| {
+                task.dependencies()
+                    .filter(|dep| dependencies.blocks(task, dep))
+            })
+            .map(str::to_string)
+            .collect();
+
+        dependencies
+    }
+
+    fn blocks(&self, task: &Task, dep: &str) -> bool {
+        self.open.contains(dep)
+            && !task
+                .stable_id()
+                .and_then(|id| self.cyclic.get(id))
+                .is_some_and(|deps| deps.contains(dep))
+    }
+
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        !task.completed && task.dependencies().any(|dep| self.blocks(task, dep))
+    }
+
+    pub fn is_blocking(&self, task: &Task) -> bool {
+        !task.completed
+            && task
+                .stable_id()
+                .is_some_and(|id| self.blocking.contains(id))
+    }
+}
+
+/// Whether `to` can be reached from `from` following the `dep:` links.
+fn reaches(links: &HashMap<&str, Vec<&str>>, from: &str, to: &str) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![from];
+
+    while let Some(id) = stack.pop() {
+        if id == to {
+            return true;
+        }
+        if visited.insert(id) {
+            stack.extend(links.get(id).into_iter().flatten());
+        }
+    }
+
+    false
+}
+
+/// The open subtasks of `parent`.
+pub fn open_children<'a>(tasks: &'a [Task], parent: &Task) -> impl Iterator<Item = &'a Task> {
+    let id = parent.stable_id();
+
+    tasks
+        .iter()
+        .filter(move |task| !task.completed && id.is_some() && task.parent() == id)
+}
+
+/// The tasks in depth-first order with their depth, each one followed by its subtasks. The order
+/// of `tasks` is kept between siblings, and tasks whose parent isn't in `tasks` are at the top.
+pub fn tree(tasks: &[Task]) -> Vec<(usize, &Task)> {
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, task) in tasks.iter().enumerate() {
+        if let Some(parent) = task.parent() {
+            children.entry(parent).or_default().push(idx);
+        }
+    }
+
+    let ids: HashSet<&str> = tasks.iter().filter_map(Task::stable_id).collect();
+    let mut visited = vec![false; tasks.len()];
+    let mut tree = Vec::with_capacity(tasks.len());
+
+    let roots =
+        (0..tasks.len()).filter(|idx| !tasks[*idx].parent().is_some_and(|p| ids.contains(p)));
+    // Tasks in a `parent:` cycle can't be reached from a root, they're added at the end
+    for idx in roots.chain(0..tasks.len()) {
+        let mut stack = vec![(0, idx)];
+        while let Some((depth, idx)) = stack.pop() {
+            if visited[idx] {
+                continue;
+            }
+            visited[idx] = true;
+            tree.push((depth, &tasks[idx]));
+
+            if let Some(subtasks) = tasks[idx].stable_id().and_then(|id| children.get(id)) {
+                stack.extend(subtasks.iter().rev().map(|child| (depth + 1, *child)));
+            }
+        }
+    }
+
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dependencies, open_children, tree};
+    use crate::tasks::task::Task;
+
+    fn tasks(lines: &[&str]) -> Vec<Task> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_blocked_and_blocking_tasks() {
+        let tasks = tasks(&[
+            "write the report id:1",
+            "send the report id:2 dep:1,3",
+            "x 2024-06-01 gather the numbers id:3",
+            "celebrate dep:2",
+        ]);
+        let dependencies = Dependencies::new(&tasks);
+
+        let blocked: Vec<bool> = tasks.iter().map(|t| dependencies.is_blocked(t)).collect();
+        assert_eq!(blocked, [false, true, false, true]);
+
+        let blocking: Vec<bool> = tasks.iter().map(|t| dependencies.is_blocking(t)).collect();
+        assert_eq!(blocking, [true, true, false, false]);
+
+        assert!(
+            tasks[0].compute_urgency(&dependencies)
+                > tasks[0].compute_urgency(&Dependencies::default())
+        );
+        assert!(tasks[3].compute_urgency(&dependencies) < 0);
+    }
+
+    #[test]
+    fn it_ignores_the_links_of_a_dependency_cycle() {
+        let tasks = tasks(&[
+            "write the report id:1 dep:2",
+            "review the report id:2 dep:3",
+            "ask for a review id:3 dep:1",
+            "send the report id:4 dep:3",
+            "think about it id:5 dep:5",
+        ]);
+        let dependencies = Dependencies::new(&tasks);
+
+        let blocked: Vec<bool> = tasks.iter().map(|t| dependencies.is_blocked(t)).collect();
+        assert_eq!(blocked, [false, false, false, true, false]);
+
+        let blocking: Vec<bool> = tasks.iter().map(|t| dependencies.is_blocking(t)).collect();
+        assert_eq!(blocking, [false, false, true, false, false]);
+    }
+
+    #[test]
+    fn it_nests_subtasks_under_their_parent() {
+        let tasks = tasks(&[
+            "step two parent:1",
+            "release id:1",
+            "unrelated",
+            "step one id:2 parent:1",
+            "step one bis parent:2",
+            "orphan parent:9",
+        ]);
+
+        let tree: Vec<(usize, usize)> = tree(&tasks)
+            .into_iter()
+            .map(|(depth, task)| (depth, task.id))
+            .collect();
+
+        assert_eq!(tree, [(0, 2), (1, 1), (1, 4), (2, 5), (0, 3), (0, 6)]);
+    }
+
+    #[test]
+    fn it_keeps_tasks_in_a_parent_cycle() {
+        let tasks = tasks(&["a id:1 parent:2", "b id:2 parent:1"]);
+
+        assert_eq!(tree(&tasks).len(), 2);
+    }
+
+    #[test]
+    fn it_finds_open_children() {
+        let tasks = tasks(&[
+            "release id:1",
+            "x 2024-06-01 step one parent:1",
+            "step two parent:1",
+            "no parent",
+        ]);
+
+        let children: Vec<usize> = open_children(&tasks, &tasks[0]).map(|t| t.id).collect();
+        assert_eq!(children, [3]);
+        assert_eq!(open_children(&tasks, &tasks[3]).count(), 0);
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::TimeDelta;
+
+use super::task::Task;
+
+/// Parse a duration tag value like `1h30m`, `2h` or `45m`. Durations too large to be
+/// represented aren't valid either.
+pub fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let mut minutes: i64 = 0;
+    let mut number = String::new();
+
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' if !number.is_empty() => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+                let n = if c == 'h' { n.checked_mul(60)? } else { n };
+                minutes = minutes.checked_add(n)?;
+            }
+            _ => return None,
+        }
+    }
+
+    // A unit is required, `90` could be minutes or hours
+    if !number.is_empty() || value.is_empty() {
+        return None;
+    }
+
+    TimeDelta::try_minutes(minutes)
+}
+
+/// Format `duration` as a tag value, in hours and minutes.
+pub fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes();
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes}m"),
+    }
+}
+
+/// The sum of `durations`, capped to the largest duration instead of overflowing.
+pub fn total_duration(durations: impl IntoIterator<Item = TimeDelta>) -> TimeDelta {
+    durations
+        .into_iter()
+        .fold(TimeDelta::zero(), |total, duration| {
+            total.checked_add(&duration).unwrap_or(TimeDelta::MAX)
+        })
+}
+
+/// The sum of the estimates of `tasks`, `None` when none of them has one.
+pub fn total_estimate<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Option<TimeDelta> {
+    let mut estimates = tasks.into_iter().filter_map(Task::estimate).peekable();
+    estimates.peek()?;
+
+    Some(total_duration(estimates))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::{format_duration, parse_duration, total_duration};
+
+    #[test]
+    fn it_parses_durations() {
+        assert_eq!(parse_duration("1h30m"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("2h"), Some(TimeDelta::hours(2)));
+        assert_eq!(parse_duration("45m"), Some(TimeDelta::minutes(45)));
+        assert_eq!(parse_duration("90m"), Some(TimeDelta::minutes(90)));
+
+        for invalid in [
+            "",
+            "90",
+            "h",
+            "1x",
+            "1h30",
+            "-1h",
+            "9999999999999h",
+            "99999999999999999999m",
+            "153722867280912930m1m",
+        ] {
+            assert_eq!(parse_duration(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn it_formats_durations() {
+        assert_eq!(format_duration(TimeDelta::minutes(90)), "1h30m");
+        assert_eq!(format_duration(TimeDelta::hours(2)), "2h");
+        assert_eq!(format_duration(TimeDelta::minutes(45)), "45m");
+        assert_eq!(format_duration(TimeDelta::zero()), "0m");
+    }
+
+    #[test]
+    fn it_caps_totals_instead_of_overflowing() {
+        let largest = parse_duration("2562047788015h").unwrap();
+
+        assert_eq!(total_duration([largest, largest]), TimeDelta::MAX);
+        assert_eq!(
+            total_duration([TimeDelta::hours(1), TimeDelta::minutes(30)]),
+            TimeDelta::minutes(90)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 
 #[derive(Debug)]
 pub enum TaskError {
@@ -8,6 +8,38 @@ pub enum TaskError {
     FailedToSave,
     FailedToOpenTodoFile,
     FailedToWriteToStdout,
+    InvalidPriority,
+    FailedToRunTui,
+    FailedToCreateTempFile,
+    EditAborted,
+    EditorNotFound,
+    EditorFailed,
+    UnknownView(String),
+    RecursiveView(String),
+    UnknownCommand(String),
+    RecursiveAlias(String),
+    FailedToRunPlugin,
+    FailedToRunHook,
+    HookRejected,
+    InvalidHookOutput,
+    DatabaseError,
+    UnsupportedByBackend,
+    FileNotFound(PathBuf),
+    OutputRequired,
+    FailedToLock,
+    FailedToRunGit,
+    SyncConflict,
+    MergeConflicts(usize),
+    FailedToImport,
+    FailedToRunServer,
+    CalDavNotConfigured,
+    FailedToReachServer,
+    InvalidServerResponse,
+    FailedToReadState(PathBuf),
+    FailedToSaveState(PathBuf),
+    LinkedToItself,
+    NoRunningTimer,
+    InvalidDuration(String),
 }
 
 impl Display for TaskError {
@@ -19,6 +51,56 @@ impl Display for TaskError {
             TaskError::FailedToSave => f.write_str("Failed to save a task"),
             TaskError::FailedToOpenTodoFile => f.write_str("Failed to open todo.txt file"),
             TaskError::FailedToWriteToStdout => f.write_str("Failed to write to stdout"),
+            TaskError::InvalidPriority => f.write_str("Priority must be a letter from A to Z"),
+            TaskError::FailedToRunTui => f.write_str("Failed to run the terminal interface"),
+            TaskError::FailedToCreateTempFile => f.write_str("Failed to create a temporary file"),
+            TaskError::EditAborted => f.write_str("Edit aborted, no changes were saved"),
+            TaskError::EditorNotFound => f.write_str(
+                "No text editor found, set $VISUAL, $EDITOR or `editor` in the config file",
+            ),
+            TaskError::EditorFailed => f.write_str("The text editor exited with an error"),
+            TaskError::UnknownView(name) => write!(f, "Unknown view `{name}`"),
+            TaskError::RecursiveView(name) => write!(f, "The view `{name}` refers to itself"),
+            TaskError::UnknownCommand(name) => {
+                write!(f, "Unknown command, alias or plugin `{name}`")
+            }
+            TaskError::RecursiveAlias(name) => write!(f, "The alias `{name}` refers to itself"),
+            TaskError::FailedToRunPlugin => f.write_str("Failed to run the plugin"),
+            TaskError::FailedToRunHook => f.write_str("Failed to run a hook"),
+            TaskError::HookRejected => f.write_str("A hook rejected the change"),
+            TaskError::InvalidHookOutput => f.write_str("A hook returned an invalid output"),
+            TaskError::DatabaseError => f.write_str("Failed to access the task database"),
+            TaskError::UnsupportedByBackend => {
+                f.write_str("This command isn't supported by the configured storage backend")
+            }
+            TaskError::FileNotFound(path) => write!(f, "File not found: {}", path.display()),
+            TaskError::OutputRequired => f.write_str("This format requires an --output file"),
+            TaskError::FailedToLock => f.write_str("Failed to lock the todo list"),
+            TaskError::FailedToRunGit => f.write_str("Failed to run git"),
+            TaskError::SyncConflict => f.write_str(
+                "The remote changes conflict with the local ones, merge them with git in the todo dir",
+            ),
+            TaskError::MergeConflicts(count) => write!(f, "{count} conflicting tasks to resolve"),
+            TaskError::FailedToImport => f.write_str("Failed to import the tasks"),
+            TaskError::FailedToRunServer => f.write_str("Failed to run the server"),
+            TaskError::CalDavNotConfigured => {
+                f.write_str("Set the collection url as `caldav.url` in the config file")
+            }
+            TaskError::FailedToReachServer => f.write_str("Failed to reach the server"),
+            TaskError::InvalidServerResponse => f.write_str("The server sent an invalid response"),
+            TaskError::FailedToReadState(path) => {
+                write!(f, "Failed to read the state file {}", path.display())
+            }
+            TaskError::FailedToSaveState(path) => {
+                write!(f, "Failed to save the state file {}", path.display())
+            }
+            TaskError::LinkedToItself => {
+                f.write_str("A task can't be its own parent or dependency")
+            }
+            TaskError::NoRunningTimer => f.write_str("No timer is running, start one with `start`"),
+            TaskError::InvalidDuration(value) => {
+                write!(f, "Invalid duration `{value}`, expected e.g. `1h30m`, `2h` or `45m`")
+            }
         }
     }
 }
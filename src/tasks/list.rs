@@ -1,11 +1,16 @@
-use super::{query::TaskQuery, task::Task};
+use chrono::TimeDelta;
+
+use super::{dependencies::Dependencies, error::TaskError, query::TaskQuery, task::Task};
 
 pub type TaskList = Vec<Task>;
 
 pub trait TaskListTrait {
     fn filter_from_query(&self, query: &TaskQuery) -> impl Iterator<Item = Task>;
     fn filter_mut_from_query(&mut self, query: &TaskQuery) -> impl Iterator<Item = &mut Task>;
-    fn sort_by_urgency(&mut self) -> TaskList;
+    fn sort_by_urgency(&mut self, dependencies: &Dependencies) -> TaskList;
+    fn stable_id_at(&mut self, idx: usize) -> String;
+    fn stable_id_of(&mut self, id: usize) -> Result<String, TaskError>;
+    fn within_capacity(&self, capacity: TimeDelta) -> TaskList;
 }
 
 impl TaskListTrait for TaskList {
@@ -76,9 +81,94 @@ impl TaskListTrait for TaskList {
         })
     }
 
-    fn sort_by_urgency(&mut self) -> TaskList {
-        self.sort_by_key(|task| task.compute_urgency());
+    fn sort_by_urgency(&mut self, dependencies: &Dependencies) -> TaskList {
+        self.sort_by_key(|task| task.compute_urgency(dependencies));
         self.reverse();
         self.to_vec()
     }
+
+    /// The `id:` tag of the task at `idx`, it's given one first if needed.
+    fn stable_id_at(&mut self, idx: usize) -> String {
+        let next = next_stable_id(self);
+
+        self[idx]
+            .tags
+            .entry("id".to_string())
+            .or_insert_with(|| next.to_string())
+            .clone()
+    }
+
+    /// The `id:` tag of the task numbered `id`, it's given one first if needed.
+    fn stable_id_of(&mut self, id: usize) -> Result<String, TaskError> {
+        let next = next_stable_id(self);
+        let Some(task) = self.iter_mut().find(|task| task.id == id) else {
+            return Err(TaskError::TaskNotFound);
+        };
+
+        Ok(task
+            .tags
+            .entry("id".to_string())
+            .or_insert_with(|| next.to_string())
+            .clone())
+    }
+
+    /// Plan a working session: going through the tasks in order, usually by urgency, keep those
+    /// whose remaining estimate still fits in `capacity`. Tasks without an estimate can't be
+    /// planned and are left out.
+    fn within_capacity(&self, capacity: TimeDelta) -> TaskList {
+        let mut available = capacity;
+
+        self.iter()
+            .filter(|task| match task.remaining() {
+                Some(remaining) if remaining <= available => {
+                    available -= remaining;
+                    true
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// The `id:` tag given to the next task without one, after the largest numeric one.
+fn next_stable_id(tasks: &[Task]) -> usize {
+    tasks
+        .iter()
+        .filter_map(|task| task.stable_id()?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::TaskListTrait;
+    use crate::tasks::{list::TaskList, task::Task};
+
+    #[test]
+    fn it_plans_the_tasks_fitting_in_the_capacity() {
+        let tasks: TaskList = [
+            "urgent est:3h",
+            "too long est:4h",
+            "not estimated",
+            "half done est:2h spent:1h30m",
+            "quick est:1h",
+            "also fits est:1h30m",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| Task::from_str(idx + 1, line).unwrap())
+        .collect();
+
+        let planned: Vec<usize> = tasks
+            .within_capacity(TimeDelta::hours(5))
+            .iter()
+            .map(|task| task.id)
+            .collect();
+
+        assert_eq!(planned, [1, 4, 5]);
+    }
 }
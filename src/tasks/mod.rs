@@ -1,3 +1,5 @@
+pub mod dependencies;
+pub mod duration;
 pub mod error;
 pub mod list;
 pub mod query;
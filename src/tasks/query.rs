@@ -4,7 +4,7 @@ use std::str::FromStr;
 
 use chrono::NaiveDate;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct TaskQuery {
     pub indexes: Vec<usize>,
     pub projects: Vec<String>,
@@ -1,11 +1,12 @@
-use chrono::{Days, Local, Months, NaiveDate};
+use chrono::{Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::Serialize;
 use std::{collections::HashMap, fmt::Display};
 
-use crate::tasks::error::TaskError;
+use crate::tasks::{dependencies::Dependencies, duration::parse_duration, error::TaskError};
 
 // TODO: handle recurrences
 // TODO: migrate away from String to &str
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize)]
 pub struct Task {
     pub id: usize,
     pub subject: String,
@@ -14,12 +15,33 @@ pub struct Task {
     pub completed_at: Option<NaiveDate>,
     pub completed: bool,
     pub due_date: Option<NaiveDate>,
+    /// The time of `due:2024-06-01T14:00`, a time given with `at:14:00` stays in the tags
+    pub due_time: Option<NaiveTime>,
     pub contexts: Vec<String>,
     pub projects: Vec<String>,
     pub hashtags: Vec<String>,
     pub tags: HashMap<String, String>,
 }
 
+const TIME_FORMAT: &str = "%H:%M";
+
+/// A `due:` value, a date with an optional time like `2024-06-01T14:00`. Nothing is kept when
+/// either of them can't be parsed.
+fn parse_due(value: &str) -> (Option<NaiveDate>, Option<NaiveTime>) {
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) => match NaiveTime::parse_from_str(time, TIME_FORMAT) {
+            Ok(time) => (date, Some(time)),
+            Err(_) => return (None, None),
+        },
+        None => (value, None),
+    };
+
+    match date.parse::<NaiveDate>() {
+        Ok(date) => (Some(date), time),
+        Err(_) => (None, None),
+    }
+}
+
 // TODO: switch from String to &str
 pub struct TaskBuilder {
     id: usize,
@@ -81,8 +103,13 @@ impl Display for Task {
 
         f.write_str(&self.subject)?;
 
-        if let Some(due_date) = self.due_date {
-            f.write_fmt(format_args!(" due:{due_date}"))?;
+        match (self.due_date, self.due_time) {
+            (Some(due_date), Some(due_time)) => f.write_fmt(format_args!(
+                " due:{due_date}T{}",
+                due_time.format(TIME_FORMAT)
+            ))?,
+            (Some(due_date), None) => f.write_fmt(format_args!(" due:{due_date}"))?,
+            _ => {}
         }
 
         for (tag, value) in &self.tags {
@@ -98,18 +125,23 @@ impl Task {
     // https://github.com/kstep/todotxt.rs/blob/master/src/lib.rs
 
     pub fn from_str(id: usize, mut s: &str) -> Result<Self, TaskError> {
-        let (completed, mut completed_at) = if s.starts_with("x ") {
-            s = &s[2..];
-            (true, s[..10].parse::<NaiveDate>().ok())
+        // Malformed lines (e.g. a completion date without any description) are reported as parse
+        // errors instead of slicing out of bounds.
+        let (completed, mut completed_at) = if let Some(rest) = s.strip_prefix("x ") {
+            s = rest;
+            (
+                true,
+                s.get(..10).and_then(|date| date.parse::<NaiveDate>().ok()),
+            )
         } else {
             (false, None)
         };
 
         if completed_at.is_some() {
-            s = &s[11..];
+            s = s.get(11..).ok_or(TaskError::FailedToParse)?;
         }
 
-        let priority = if s.starts_with('(') && &s[2..4] == ") " {
+        let priority = if s.starts_with('(') && s.get(2..4) == Some(") ") {
             match s.as_bytes()[1] as char {
                 p @ 'A'..='Z' => {
                     s = &s[4..];
@@ -121,14 +153,13 @@ impl Task {
             None
         };
 
-        let mut created_at = if s.len() < 10 {
-            None
-        } else if let Ok(date) = s[..10].parse::<NaiveDate>() {
-            s = &s[11..];
-            Some(date)
-        } else {
-            None
-        };
+        let mut created_at =
+            if let Some(date) = s.get(..10).and_then(|date| date.parse::<NaiveDate>().ok()) {
+                s = s.get(11..).ok_or(TaskError::FailedToParse)?;
+                Some(date)
+            } else {
+                None
+            };
 
         // If there's no priority and no completion date in the string, the creation date could be
         // parsed as the completion date, so if it's the case we fix it.
@@ -138,6 +169,18 @@ impl Task {
             completed_at = None;
         }
 
+        Ok(Task {
+            priority,
+            created_at,
+            completed_at,
+            completed,
+            ..Self::from_subject(id, s)?
+        })
+    }
+
+    /// Parse `s` as the subject of a task only, with its contexts, projects, hashtags and tags. A
+    /// leading `x `, priority or date is part of the subject, unlike with [`Task::from_str`].
+    pub fn from_subject(id: usize, s: &str) -> Result<Self, TaskError> {
         let buf = s;
 
         let mut subject = Vec::new();
@@ -160,6 +203,7 @@ impl Task {
 
         // Some tag we know about
         let mut due_date = None;
+        let mut due_time = None;
 
         // NOTE: we must iter on `buf` that way to support non-ascii chars
         let buf_iter = buf.char_indices();
@@ -193,7 +237,7 @@ impl Task {
                     if i - k > 1 {
                         match &buf[j..k] {
                             "due" => {
-                                due_date = buf[k + 1..i].parse::<NaiveDate>().ok();
+                                (due_date, due_time) = parse_due(&buf[k + 1..i]);
                             }
                             tag => {
                                 tags.insert(tag.to_string(), buf[k + 1..i].to_string());
@@ -205,9 +249,14 @@ impl Task {
                 _ => state,
             };
 
+            // Projects, contexts and hashtags stay in the subject wherever they are, a hashtag
+            // followed by more words was dropped from it and lost when the task was saved
             if new_state == State::Init {
                 match state {
-                    State::TagBegin(j) | State::Project(j) | State::Context(j) => {
+                    State::TagBegin(j)
+                    | State::Project(j)
+                    | State::Context(j)
+                    | State::HashTag(j) => {
                         subject.extend(&buf.as_bytes()[j..i + 1]);
                     }
                     State::Init => subject.push(buf.as_bytes()[i]),
@@ -222,7 +271,7 @@ impl Task {
         match state {
             State::TagEnd(j, k) => match &buf[j..k] {
                 "due" => {
-                    due_date = buf[k + 1..].parse::<NaiveDate>().ok();
+                    (due_date, due_time) = parse_due(&buf[k + 1..]);
                 }
                 tag => {
                     tags.insert(tag.to_string(), buf[k + 1..].to_string());
@@ -256,18 +305,74 @@ impl Task {
         Ok(Task {
             id,
             subject,
-            priority,
-            created_at,
-            completed_at,
-            completed,
             due_date,
+            due_time,
             contexts,
             projects,
             hashtags,
             tags,
+            ..Task::default()
+        })
+    }
+
+    /// The time of day the task is due, from `due:` or from its `at:` tag.
+    pub fn due_time_of_day(&self) -> Option<NaiveTime> {
+        self.due_time.or_else(|| {
+            let at = self.tags.get("at")?;
+            NaiveTime::parse_from_str(at, TIME_FORMAT).ok()
         })
     }
 
+    /// When the task is due, only when it's due at a given time of day.
+    pub fn due_at(&self) -> Option<NaiveDateTime> {
+        Some(self.due_date?.and_time(self.due_time_of_day()?))
+    }
+
+    /// The key to sort tasks by due date. A date without a time means the task is due sometime
+    /// that day, with no hour to compare: it comes before the tasks due at a time on that day, and
+    /// its urgency only depends on the date.
+    pub fn due_key(&self) -> Option<(NaiveDate, Option<NaiveTime>)> {
+        Some((self.due_date?, self.due_time_of_day()))
+    }
+
+    /// The `id:` tag, unlike `id` it doesn't change when other tasks are added or removed.
+    pub fn stable_id(&self) -> Option<&str> {
+        self.tags.get("id").map(String::as_str)
+    }
+
+    /// The `id:` of the task this one is a subtask of, from its `parent:` tag.
+    pub fn parent(&self) -> Option<&str> {
+        self.tags.get("parent").map(String::as_str)
+    }
+
+    /// The `id:` of the tasks blocking this one, from its `dep:` tag, e.g. `dep:3,12`.
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.tags
+            .get("dep")
+            .into_iter()
+            .flat_map(|deps| deps.split(','))
+            .filter(|dep| !dep.is_empty())
+    }
+
+    /// The time tracked on the task, from its `spent:` tag.
+    pub fn spent(&self) -> Option<TimeDelta> {
+        self.tags
+            .get("spent")
+            .and_then(|spent| parse_duration(spent))
+    }
+
+    /// The estimated effort, from its `est:` tag.
+    pub fn estimate(&self) -> Option<TimeDelta> {
+        self.tags.get("est").and_then(|est| parse_duration(est))
+    }
+
+    /// What's left of the estimate once the time spent is deducted.
+    pub fn remaining(&self) -> Option<TimeDelta> {
+        let remaining = self.estimate()? - self.spent().unwrap_or_default();
+
+        Some(remaining.max(TimeDelta::zero()))
+    }
+
     pub fn complete(&mut self) {
         self.completed = true;
         self.completed_at = Some(Local::now().date_naive());
@@ -281,7 +386,7 @@ impl Task {
         // bring it back
     }
 
-    pub fn compute_urgency(&self) -> i32 {
+    pub fn compute_urgency(&self, dependencies: &Dependencies) -> i32 {
         // https://taskwarrior.org/docs/urgency/
         // taskwarrior urgency coefficients
         // FIXME: make all those variable configurable
@@ -292,6 +397,8 @@ impl Task {
         // urgency.uda.priority.L.coefficient           1.8 # low Priority
         // urgency.age.coefficient                      2.0 # coefficient for age
         // urgency.project.coefficient                  1.0 # assigned to any project
+        // urgency.blocking.coefficient                 8.0 # blocking other tasks
+        // urgency.blocked.coefficient                 -5.0 # blocked by other tasks
         let mut urgency = 0;
 
         if self.hashtags.contains(&String::from("next")) {
@@ -306,17 +413,7 @@ impl Task {
             urgency -= 30;
         }
 
-        let due_start_to_be_urgent = Local::now()
-            .date_naive()
-            // FIXME: make the number of days configurable
-            .checked_sub_days(Days::new(2))
-            .expect("Failed to compute the day when due tasks become urgent.");
-        if self
-            .due_date
-            .is_some_and(|date| date <= due_start_to_be_urgent)
-        {
-            urgency += 12;
-        }
+        urgency += self.due_urgency(Local::now().naive_local());
 
         let pri_urgency = match &self.priority {
             Some('A') => 6,
@@ -341,13 +438,49 @@ impl Task {
             urgency += 1;
         }
 
+        if dependencies.is_blocking(self) {
+            urgency += 8;
+        }
+
+        if dependencies.is_blocked(self) {
+            urgency -= 5;
+        }
+
         urgency
     }
+
+    fn due_urgency(&self, now: NaiveDateTime) -> i32 {
+        // With a time, the urgency rises in the last hours before it
+        if let Some(due_at) = self.due_at() {
+            let left = due_at - now;
+            return match left.num_hours() {
+                _ if left <= TimeDelta::zero() => 12,
+                0..2 => 9,
+                2..6 => 6,
+                6..24 => 3,
+                _ => 0,
+            };
+        }
+
+        let due_start_to_be_urgent = now
+            .date()
+            // FIXME: make the number of days configurable
+            .checked_sub_days(Days::new(2))
+            .expect("Failed to compute the day when due tasks become urgent.");
+        if self
+            .due_date
+            .is_some_and(|date| date <= due_start_to_be_urgent)
+        {
+            12
+        } else {
+            0
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{HashMap, Local, NaiveDate, Task};
+    use super::{HashMap, Local, NaiveDate, NaiveTime, Task};
 
     #[test]
     fn it_parses_task() {
@@ -531,6 +664,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn it_keeps_hash_tags_in_the_middle_of_the_subject() {
+        let task = Task::from_str(0, "review #next the pull request").unwrap();
+
+        assert_eq!(task.subject, "review #next the pull request");
+        assert_eq!(task.hashtags, vec!["next".to_string()]);
+    }
+
+    #[test]
+    fn it_keeps_every_hash_tag_when_saved_again() {
+        let line = "write report #next #office +work";
+        let task = Task::from_str(0, line).unwrap();
+
+        assert_eq!(
+            task.hashtags,
+            vec!["next".to_string(), "office".to_string()]
+        );
+        assert_eq!(task.to_string(), line);
+    }
+
+    #[test]
+    fn it_fails_to_parse_completed_task_without_description() {
+        assert!(Task::from_str(0, "x 2024-05-01").is_err());
+        assert!(Task::from_str(0, "(A) 2024-05-01").is_err());
+    }
+
+    #[test]
+    fn it_parses_task_starting_with_multibyte_chars() {
+        let task = Task::from_str(0, "(A éé").unwrap();
+
+        assert_eq!(task.subject, "(A éé");
+        assert_eq!(task.priority, None);
+    }
+
     #[test]
     fn it_display_task() {
         let task = Task {
@@ -632,4 +799,50 @@ mod tests {
         assert_eq!(task.completed_at, Some(Local::now().date_naive()));
         assert!(task.completed);
     }
+
+    #[test]
+    fn it_parses_and_displays_a_due_time() {
+        let line = "call the plumber due:2024-06-01T14:00";
+        let task = Task::from_str(0, line).unwrap();
+
+        assert_eq!(task.due_date, NaiveDate::from_ymd_opt(2024, 6, 1));
+        assert_eq!(task.due_time, NaiveTime::from_hms_opt(14, 0, 0));
+        assert_eq!(task.to_string(), line);
+
+        let task = Task::from_str(0, "call the plumber due:2024-06-01T25:00").unwrap();
+        assert_eq!((task.due_date, task.due_time), (None, None));
+    }
+
+    #[test]
+    fn it_reads_the_due_time_from_the_at_tag() {
+        let line = "call the plumber due:2024-06-01 at:09:30";
+        let task = Task::from_str(0, line).unwrap();
+
+        assert_eq!(task.due_time, None);
+        assert_eq!(
+            task.due_at(),
+            NaiveDate::from_ymd_opt(2024, 6, 1).and_then(|d| d.and_hms_opt(9, 30, 0))
+        );
+        assert_eq!(task.to_string(), line);
+        assert_eq!(Task::from_str(0, "call at:09:30").unwrap().due_at(), None);
+    }
+
+    #[test]
+    fn it_gets_more_urgent_in_the_last_hours() {
+        let task = Task::from_str(0, "meeting due:2024-06-01T14:00").unwrap();
+        let at = |hour, min| {
+            NaiveDate::from_ymd_opt(2024, 6, 1)
+                .and_then(|d| d.and_hms_opt(hour, min, 0))
+                .unwrap()
+        };
+
+        let urgencies: Vec<i32> = [at(0, 0), at(9, 0), at(13, 0), at(14, 0)]
+            .into_iter()
+            .map(|now| task.due_urgency(now))
+            .collect();
+        assert_eq!(urgencies, [3, 6, 9, 12]);
+
+        let date_only = Task::from_str(0, "meeting due:2024-06-01").unwrap();
+        assert_eq!(date_only.due_urgency(at(13, 0)), 0);
+    }
 }
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    tasks::error::TaskError,
+    utils::{load_json, save_json},
+};
+
+/// The running timer of `todors start`, kept in the todo dir until `todors stop`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Timer {
+    /// The `id:` tag of the task, its number may change while the timer runs
+    pub task: String,
+    pub started_at: DateTime<Local>,
+}
+
+impl Timer {
+    pub fn new(task: String) -> Self {
+        Self {
+            task,
+            started_at: Local::now(),
+        }
+    }
+
+    /// The time since the timer started, rounded to the minute.
+    pub fn elapsed(&self, now: DateTime<Local>) -> TimeDelta {
+        let seconds = (now - self.started_at).num_seconds().max(0);
+
+        TimeDelta::minutes((seconds + 30) / 60)
+    }
+}
+
+pub struct TimerFile {
+    path: PathBuf,
+}
+
+impl TimerFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Option<Timer>, TaskError> {
+        load_json(&self.path)
+    }
+
+    pub fn save(&self, timer: &Timer) -> Result<(), TaskError> {
+        save_json(&self.path, timer)
+    }
+
+    pub fn clear(&self) -> Result<(), TaskError> {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(_) => Err(TaskError::FailedToSave),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeDelta};
+
+    use super::{Timer, TimerFile};
+
+    #[test]
+    fn it_rounds_the_elapsed_time_to_the_minute() {
+        let timer = Timer::new("1".to_string());
+
+        let elapsed = |seconds| timer.elapsed(timer.started_at + TimeDelta::seconds(seconds));
+        assert_eq!(elapsed(29), TimeDelta::zero());
+        assert_eq!(elapsed(90), TimeDelta::minutes(2));
+        assert_eq!(elapsed(5400), TimeDelta::minutes(90));
+        assert_eq!(
+            timer.elapsed(timer.started_at - TimeDelta::hours(1)),
+            TimeDelta::zero()
+        );
+    }
+
+    #[test]
+    fn it_saves_and_clears_the_timer() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = TimerFile::new(dir.path().join(".timer.json"));
+        assert_eq!(file.load().unwrap(), None);
+
+        let timer = Timer {
+            task: "3".to_string(),
+            started_at: Local::now(),
+        };
+        file.save(&timer).unwrap();
+        assert_eq!(file.load().unwrap(), Some(timer));
+
+        file.clear().unwrap();
+        assert_eq!(file.load().unwrap(), None);
+        file.clear().unwrap();
+    }
+}
@@ -0,0 +1,363 @@
+use chrono::NaiveDate;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::ListState;
+
+use crate::{
+    repository::{TaskUpdate, TodoRepository},
+    tasks::{
+        dependencies::Dependencies,
+        error::TaskError,
+        list::{TaskList, TaskListTrait},
+        query::TaskQuery,
+        task::Task,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Filter,
+    Add,
+    Priority,
+    DueDate,
+    ConfirmDelete,
+}
+
+pub struct App {
+    repo: TodoRepository,
+    pub tasks: TaskList,
+    pub visible: TaskList,
+    pub filter: String,
+    pub input: String,
+    pub mode: Mode,
+    pub state: ListState,
+    pub message: Option<String>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new(repo: TodoRepository) -> Result<Self, TaskError> {
+        let mut app = Self {
+            repo,
+            tasks: TaskList::new(),
+            visible: TaskList::new(),
+            filter: String::new(),
+            input: String::new(),
+            mode: Mode::Normal,
+            state: ListState::default(),
+            message: None,
+            should_quit: false,
+        };
+        app.reload()?;
+
+        Ok(app)
+    }
+
+    /// Re-read the todo file and recompute the visible tasks from the current filter.
+    pub fn reload(&mut self) -> Result<(), TaskError> {
+        self.tasks = self.repo.all()?;
+        self.refresh()
+    }
+
+    fn refresh(&mut self) -> Result<(), TaskError> {
+        let mut visible: TaskList = if self.filter.trim().is_empty() {
+            self.tasks.clone()
+        } else {
+            let query = self.filter.parse::<TaskQuery>()?;
+            self.tasks.filter_from_query(&query).collect()
+        };
+        self.visible = visible.sort_by_urgency(&Dependencies::new(&self.tasks));
+
+        let selected = match self.state.selected() {
+            _ if self.visible.is_empty() => None,
+            Some(idx) => Some(idx.min(self.visible.len() - 1)),
+            None => Some(0),
+        };
+        self.state.select(selected);
+
+        Ok(())
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.state.selected().and_then(|idx| self.visible.get(idx))
+    }
+
+    /// A query matching only the selected task, so changes go through the repository.
+    fn selected_query(&self) -> Option<TaskQuery> {
+        self.selected_task().map(|task| TaskQuery {
+            indexes: vec![task.id],
+            ..TaskQuery::default()
+        })
+    }
+
+    fn toggle_selected(&mut self) -> Result<(), TaskError> {
+        let Some(query) = self.selected_query() else {
+            return Ok(());
+        };
+
+        if self.selected_task().is_some_and(|task| task.completed) {
+            self.repo.uncomplete(&query)?;
+        } else {
+            self.repo.complete(&query)?;
+        }
+
+        self.reload()
+    }
+
+    fn update_selected(&mut self, update: TaskUpdate) -> Result<(), TaskError> {
+        let Some(query) = self.selected_query() else {
+            return Ok(());
+        };
+
+        self.repo.modify(&query, &update)?;
+        self.reload()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<(), TaskError> {
+        self.message = None;
+
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::ConfirmDelete => {
+                if let KeyCode::Char('y') = key.code {
+                    self.delete_selected()?;
+                }
+                self.mode = Mode::Normal;
+                Ok(())
+            }
+            _ => self.handle_input_key(key),
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Result<(), TaskError> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Char('g') | KeyCode::Home => self.state.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.state.select_last(),
+            KeyCode::Char('x') | KeyCode::Char(' ') => self.toggle_selected()?,
+            KeyCode::Char('r') => self.reload()?,
+            KeyCode::Char('/') => self.start_input(Mode::Filter, self.filter.clone()),
+            KeyCode::Char('a') => self.start_input(Mode::Add, String::new()),
+            KeyCode::Char('p') => {
+                let current = self
+                    .selected_task()
+                    .and_then(|task| task.priority)
+                    .map(String::from)
+                    .unwrap_or_default();
+                self.start_input(Mode::Priority, current)
+            }
+            KeyCode::Char('d') => {
+                let current = self
+                    .selected_task()
+                    .and_then(|task| task.due_date)
+                    .map(|date| date.to_string())
+                    .unwrap_or_default();
+                self.start_input(Mode::DueDate, current)
+            }
+            KeyCode::Char('D') | KeyCode::Delete if self.selected_task().is_some() => {
+                self.mode = Mode::ConfirmDelete
+            }
+            _ => {}
+        }
+
+        // `select_next` and `select_last` may go past the end of the list, the widget clamps the
+        // index when rendering but we want `selected_task` to be accurate right away.
+        if let Some(idx) = self.state.selected()
+            && idx >= self.visible.len()
+        {
+            self.state.select(self.visible.len().checked_sub(1));
+        }
+
+        Ok(())
+    }
+
+    fn start_input(&mut self, mode: Mode, initial: String) {
+        self.mode = mode;
+        self.input = initial;
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Result<(), TaskError> {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                self.input.clear();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.input);
+                let mode = std::mem::replace(&mut self.mode, Mode::Normal);
+                if let Err(err) = self.submit(mode, input.trim()) {
+                    self.message = Some(err.to_string());
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn submit(&mut self, mode: Mode, input: &str) -> Result<(), TaskError> {
+        match mode {
+            Mode::Filter => {
+                // Validate the query before replacing the current filter
+                input.parse::<TaskQuery>()?;
+                self.filter = input.to_string();
+                self.refresh()
+            }
+            Mode::Add => {
+                if input.is_empty() {
+                    return Ok(());
+                }
+                self.repo.add(input, None)?;
+                self.reload()
+            }
+            Mode::Priority => {
+                let priority = match input.chars().next().map(|c| c.to_ascii_uppercase()) {
+                    None => None,
+                    Some(p @ 'A'..='Z') if input.len() == 1 => Some(p),
+                    Some(_) => return Err(TaskError::InvalidPriority),
+                };
+                self.update_selected(TaskUpdate {
+                    priority: Some(priority),
+                    ..TaskUpdate::default()
+                })
+            }
+            Mode::DueDate => {
+                let due_date = if input.is_empty() {
+                    None
+                } else {
+                    Some(
+                        input
+                            .parse::<NaiveDate>()
+                            .map_err(|_| TaskError::FailedToParse)?,
+                    )
+                };
+                self.update_selected(TaskUpdate {
+                    due_date: Some(due_date),
+                    ..TaskUpdate::default()
+                })
+            }
+            Mode::Normal | Mode::ConfirmDelete => Ok(()),
+        }
+    }
+
+    fn delete_selected(&mut self) -> Result<(), TaskError> {
+        let Some(query) = self.selected_query() else {
+            return Ok(());
+        };
+
+        self.repo.remove(&query)?;
+        self.reload()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{App, KeyCode, KeyEvent, Mode};
+    use crate::{repository::TodoRepository, storage::TaskStorage};
+
+    fn app_with(content: &str) -> (tempfile::NamedTempFile, App) {
+        let todo_file =
+            tempfile::NamedTempFile::new().expect("Failed to create temporary file for the test");
+        std::fs::write(todo_file.path(), content).unwrap();
+
+        let app = App::new(TodoRepository::new(TaskStorage::new(
+            todo_file.path().to_path_buf(),
+        )))
+        .unwrap();
+
+        (todo_file, app)
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            app.handle_key(KeyEvent::from(code)).unwrap();
+        }
+    }
+
+    #[test]
+    fn it_sorts_tasks_by_urgency() {
+        let (_file, app) = app_with("first\n(A) second\n");
+
+        assert_eq!(app.visible[0].subject, "second");
+        assert_eq!(app.selected_task().unwrap().subject, "second");
+    }
+
+    #[test]
+    fn it_filters_with_task_query() {
+        let (_file, mut app) = app_with("first +work\nsecond +home\n");
+
+        press(&mut app, "/+home\n");
+
+        assert_eq!(app.filter, "+home");
+        assert_eq!(app.visible.len(), 1);
+        assert_eq!(app.visible[0].subject, "second +home");
+    }
+
+    #[test]
+    fn it_toggles_done_and_persists() {
+        let (file, mut app) = app_with("2024-05-01 first\n");
+
+        press(&mut app, "x");
+        assert!(
+            std::fs::read_to_string(file.path())
+                .unwrap()
+                .starts_with("x ")
+        );
+
+        press(&mut app, "x");
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "2024-05-01 first\n"
+        );
+    }
+
+    #[test]
+    fn it_sets_priority_and_due_date() {
+        let (file, mut app) = app_with("first\n");
+
+        press(&mut app, "pb\n");
+        press(&mut app, "d2024-06-01\n");
+
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "(B) first due:2024-06-01\n"
+        );
+    }
+
+    #[test]
+    fn it_reports_invalid_due_date() {
+        let (_file, mut app) = app_with("first\n");
+
+        press(&mut app, "dtomorrow\n");
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.message.is_some());
+        assert_eq!(app.visible[0].due_date, None);
+    }
+
+    #[test]
+    fn it_adds_and_deletes_tasks() {
+        let (file, mut app) = app_with("first\n");
+
+        press(&mut app, "a(A) second\n");
+        assert_eq!(app.tasks.len(), 2);
+        assert_eq!(app.selected_task().unwrap().subject, "second");
+
+        press(&mut app, "Dy");
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "first\n");
+    }
+}
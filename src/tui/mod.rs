@@ -0,0 +1,43 @@
+mod app;
+mod ui;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::{repository::TodoRepository, tasks::error::TaskError};
+
+pub use app::{App, Mode};
+
+pub fn run(repo: TodoRepository) -> Result<(), TaskError> {
+    let mut app = App::new(repo)?;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+
+    result
+}
+
+fn event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Result<(), TaskError> {
+    while !app.should_quit {
+        if let Err(err) = terminal.draw(|frame| ui::draw(frame, app)) {
+            eprintln!("Failed to draw the interface: {err}");
+            return Err(TaskError::FailedToRunTui);
+        }
+
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                // Errors are shown in the status bar instead of leaving the interface
+                if let Err(err) = app.handle_key(key) {
+                    app.message = Some(err.to_string());
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("Failed to read terminal events: {err}");
+                return Err(TaskError::FailedToRunTui);
+            }
+        }
+    }
+
+    Ok(())
+}
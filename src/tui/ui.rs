@@ -0,0 +1,81 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, Paragraph},
+};
+
+use super::app::{App, Mode};
+use crate::tasks::task::Task;
+
+const HELP: &str =
+    "j/k move  x done  p priority  d due  a add  D delete  / filter  r reload  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [filter_area, list_area, status_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let (filter_title, filter_text) = match app.mode {
+        Mode::Filter => ("Filter (enter to apply, esc to cancel)", app.input.as_str()),
+        _ => ("Filter", app.filter.as_str()),
+    };
+    let filter = Paragraph::new(filter_text).block(Block::bordered().title(filter_title));
+    frame.render_widget(filter, filter_area);
+    if app.mode == Mode::Filter {
+        frame.set_cursor_position((
+            filter_area.x + 1 + app.input.chars().count() as u16,
+            filter_area.y + 1,
+        ));
+    }
+
+    let width = app.tasks.len().checked_ilog10().unwrap_or(0) as usize + 1;
+    let items: Vec<ListItem> = app
+        .visible
+        .iter()
+        .map(|task| ListItem::new(format!("{:0width$}) {}", task.id, task)).style(style(task)))
+        .collect();
+    let title = format!(" {}/{} tasks ", app.visible.len(), app.tasks.len());
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, list_area, &mut app.state);
+
+    let status = match (app.mode, &app.message) {
+        (Mode::Add, _) => prompt("Add: ", &app.input),
+        (Mode::Priority, _) => prompt("Priority (A-Z, empty to remove): ", &app.input),
+        (Mode::DueDate, _) => prompt("Due date (YYYY-MM-DD, empty to remove): ", &app.input),
+        (Mode::ConfirmDelete, _) => Line::from("Delete the selected task? (y/n)".red()),
+        (_, Some(message)) => Line::from(message.as_str().red()),
+        _ => Line::from(HELP.dark_gray()),
+    };
+    if matches!(app.mode, Mode::Add | Mode::Priority | Mode::DueDate) {
+        frame.set_cursor_position((status_area.x + status.width() as u16, status_area.y));
+    }
+    frame.render_widget(Paragraph::new(status), status_area);
+}
+
+fn prompt<'a>(label: &'a str, input: &'a str) -> Line<'a> {
+    Line::from(vec![Span::from(label).bold(), Span::from(input)])
+}
+
+// Keep the same colors as `print_tasks_list` so both views feel the same
+fn style(task: &Task) -> Style {
+    if task.completed {
+        return Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::CROSSED_OUT);
+    }
+
+    match task.priority {
+        Some('A') => Style::default().fg(Color::Magenta).bold(),
+        Some('B') => Style::default().fg(Color::Yellow).bold(),
+        Some('C') => Style::default().fg(Color::Green).bold(),
+        Some(_) => Style::default().fg(Color::Blue).bold(),
+        None => Style::default(),
+    }
+}
@@ -1,48 +1,210 @@
+use crate::tasks::duration::{format_duration, total_estimate};
 use crate::tasks::error::TaskError;
 use crate::tasks::list::TaskList;
+use crate::tasks::task::Task;
 use colored::Colorize;
+use serde::{Serialize, de::DeserializeOwned};
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 pub fn print_tasks_list(tasks: &TaskList, total: usize) -> Result<(), TaskError> {
+    let tasks: Vec<(usize, &Task)> = tasks.iter().map(|task| (0, task)).collect();
+
+    print_tasks_tree(&tasks, total)
+}
+
+/// Like [`print_tasks_list`], with the tasks indented by their depth, e.g. subtasks under their
+/// parent.
+pub fn print_tasks_tree(tasks: &[(usize, &Task)], total: usize) -> Result<(), TaskError> {
+    print_tasks_groups(&[(None, tasks.to_vec())], total)
+}
+
+/// Tasks with their depth in the tree, under an optional header.
+pub type TaskGroup<'a> = (Option<String>, Vec<(usize, &'a Task)>);
+
+/// Like [`print_tasks_tree`], each group under its header followed by its estimated total.
+pub fn print_tasks_groups(groups: &[TaskGroup], total: usize) -> Result<(), TaskError> {
     let stdout = io::stdout();
     let mut handle = io::BufWriter::new(stdout);
 
+    let printed: Vec<&Task> = groups
+        .iter()
+        .flat_map(|(_, tasks)| tasks.iter().map(|(_, task)| *task))
+        .collect();
+    let estimated = |tasks: &[&Task]| {
+        total_estimate(tasks.iter().copied())
+            .map(|estimate| format!(", {} estimated", format_duration(estimate)))
+            .unwrap_or_default()
+    };
+
     // FIXME: find the right way to display colors for completed and prioritized tasks
     // Maybe the solution is to put the logic in list item
-    let width: usize = ((tasks.len() + 1).checked_ilog10().unwrap_or(0) + 1)
+    let width: usize = ((printed.len() + 1).checked_ilog10().unwrap_or(0) + 1)
         .try_into()
         .expect("Failed to parse task list length width");
-    for task in tasks {
-        let mut line = format!("{:0width$}) {}", task.id, task, width = width);
-        if let Some(priority) = task.priority {
-            line = match priority {
-                'A' => line.magenta().bold().to_string(),
-                'B' => line.yellow().bold().to_string(),
-                'C' => line.green().bold().to_string(),
-                _ => line.blue().bold().to_string(),
+    for (header, tasks) in groups {
+        if let Some(header) = header {
+            let header = match total_estimate(tasks.iter().map(|(_, task)| *task)) {
+                Some(estimate) => format!("{header} ({} estimated)", format_duration(estimate)),
+                None => header.clone(),
             };
+            write_line(&mut handle, &header.bold().to_string())?;
+        }
+
+        for (depth, task) in tasks {
+            let indent = "  ".repeat(*depth);
+            let mut line = format!("{:0width$}) {indent}{}", task.id, task, width = width);
+            if let Some(priority) = task.priority {
+                line = match priority {
+                    'A' => line.magenta().bold().to_string(),
+                    'B' => line.yellow().bold().to_string(),
+                    'C' => line.green().bold().to_string(),
+                    _ => line.blue().bold().to_string(),
+                };
+            }
+            write_line(&mut handle, &line)?;
         }
-        match writeln!(handle, "{line}") {
-            Ok(_) => {}
-            Err(err) => {
-                eprint!("Failed to write tasks list to stdout: {err}");
-                return Err(TaskError::FailedToWriteToStdout);
+    }
+
+    write_line(
+        &mut handle,
+        &format!(
+            "⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯\n{}/{} tasks where printed{}",
+            printed.len(),
+            total,
+            estimated(&printed)
+        ),
+    )
+}
+
+fn write_line(handle: &mut impl Write, line: &str) -> Result<(), TaskError> {
+    writeln!(handle, "{line}").map_err(|err| {
+        eprint!("Failed to write tasks list to stdout: {err}");
+        TaskError::FailedToWriteToStdout
+    })
+}
+
+/// Look for an executable named `name` in the directories of the `PATH` env var.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+
+    find_executable_in(std::env::split_paths(&paths), name)
+}
+
+pub fn find_executable_in(dirs: impl IntoIterator<Item = PathBuf>, name: &str) -> Option<PathBuf> {
+    dirs.into_iter()
+        .map(|dir| dir.join(name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Split a command like `code --wait` in a program and its arguments. Single and double quotes
+/// can be used to keep spaces in an argument.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut in_arg = false;
+
+    for c in command.chars() {
+        match (c, quote) {
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (c, _) => {
+                current.push(c);
+                in_arg = true;
             }
         }
     }
-    match writeln!(
-        handle,
-        "⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯\n{}/{} tasks where printed",
-        tasks.len(),
-        total
-    ) {
-        Ok(_) => {}
+
+    if in_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Read the JSON state kept in the todo dir, `None` when there is none yet.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, TaskError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
         Err(err) => {
-            eprint!("Failed to write tasks list to stdout: {err}");
-            return Err(TaskError::FailedToWriteToStdout);
+            eprintln!("Failed to read {}: {err}", path.display());
+            return Err(TaskError::FailedToReadState(path.to_path_buf()));
         }
+    };
+
+    serde_json::from_str(&content).map(Some).map_err(|err| {
+        eprintln!("Invalid JSON in {}: {err}", path.display());
+        TaskError::FailedToReadState(path.to_path_buf())
+    })
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), TaskError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|err| err.to_string())
+        .and_then(|content| std::fs::write(path, content).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            eprintln!("Failed to write {}: {err}", path.display());
+            TaskError::FailedToSaveState(path.to_path_buf())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_json, save_json, split_command};
+
+    #[test]
+    fn it_splits_command_with_arguments() {
+        assert_eq!(split_command("code --wait"), vec!["code", "--wait"]);
+        assert_eq!(split_command("  nvim  "), vec!["nvim"]);
     }
 
-    Ok(())
+    #[test]
+    fn it_keeps_quoted_arguments_together() {
+        assert_eq!(
+            split_command("\"/opt/my editor/bin\" -c 'set ft=todo' ''"),
+            vec!["/opt/my editor/bin", "-c", "set ft=todo", ""]
+        );
+    }
+
+    #[test]
+    fn it_loads_the_saved_json_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        assert_eq!(load_json::<Vec<String>>(&path).unwrap(), None);
+
+        save_json(&path, &vec!["one".to_string()]).unwrap();
+        assert_eq!(
+            load_json::<Vec<String>>(&path).unwrap(),
+            Some(vec!["one".to_string()])
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_json::<Vec<String>>(&path).is_err());
+    }
 }
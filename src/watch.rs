@@ -0,0 +1,124 @@
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the watched file is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What changes when a file is written: its modification time and its size, and its inode when
+/// an editor saves by renaming a new file over it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    inode: u64,
+}
+
+#[cfg(unix)]
+fn inode(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn inode(_meta: &std::fs::Metadata) -> u64 {
+    0
+}
+
+/// `None` while the file doesn't exist, e.g. between an editor removing it and renaming the new
+/// version.
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = std::fs::metadata(path).ok()?;
+
+    Some(Stamp {
+        modified: meta.modified().ok(),
+        len: meta.len(),
+        inode: inode(&meta),
+    })
+}
+
+/// Wait for changes to a file by polling it, the path is checked again each time so it keeps
+/// working when the file is replaced.
+pub struct Watcher {
+    path: PathBuf,
+    seen: Option<Stamp>,
+    debounce: Duration,
+}
+
+impl Watcher {
+    pub fn new(path: PathBuf, debounce: Duration) -> Self {
+        let seen = stamp(&path);
+
+        Self {
+            path,
+            seen,
+            debounce,
+        }
+    }
+
+    /// Whether the file changed since it was last seen.
+    pub fn changed(&mut self) -> bool {
+        let current = stamp(&self.path);
+        if current == self.seen {
+            return false;
+        }
+
+        self.seen = current;
+        true
+    }
+
+    /// Block until the file changed and then stayed the same for the debounce delay, so a save
+    /// made in several steps is only reported once.
+    pub fn wait(&mut self) {
+        while !self.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        loop {
+            thread::sleep(self.debounce);
+            if !self.changed() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Watcher;
+
+    #[test]
+    fn it_sees_a_file_replaced_by_a_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "call mom\n").unwrap();
+
+        let mut watcher = Watcher::new(path.clone(), Duration::ZERO);
+        assert!(!watcher.changed());
+
+        let saved = dir.path().join(".todo.txt.swp");
+        std::fs::write(&saved, "call mom\n").unwrap();
+        std::fs::rename(&saved, &path).unwrap();
+
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+
+    #[test]
+    fn it_sees_a_file_removed_and_created_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo.txt");
+
+        let mut watcher = Watcher::new(path.clone(), Duration::ZERO);
+        std::fs::write(&path, "call mom\n").unwrap();
+        assert!(watcher.changed());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+    }
+}
@@ -1,5 +1,6 @@
 use todors::cli::Add;
 use todors::handlers::handle_add;
+use todors::repository::TodoRepository;
 use todors::storage::TaskStorage;
 
 #[test]
@@ -20,7 +21,7 @@ fn it_add_the_new_task() {
         None,
     );
 
-    handle_add(params, storage).unwrap();
+    handle_add(params, TodoRepository::new(storage)).unwrap();
 
     let result_file = std::fs::read_to_string(todo_file_name).unwrap();
 
@@ -45,7 +46,7 @@ fn it_add_the_new_task_with_priority_pass_as_arg() {
         Some('A'),
     );
 
-    handle_add(params, storage).unwrap();
+    handle_add(params, TodoRepository::new(storage)).unwrap();
 
     let result_file = std::fs::read_to_string(todo_file_name).unwrap();
 
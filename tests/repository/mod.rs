@@ -0,0 +1,101 @@
+use chrono::{NaiveDate, TimeDelta};
+use todors::storage::TaskStorage;
+use todors::tasks::query::TaskQuery;
+use todors::{TaskUpdate, TodoRepository};
+
+fn repository_with(content: &str) -> (tempfile::NamedTempFile, TodoRepository) {
+    let todo_file =
+        tempfile::NamedTempFile::new().expect("Failed to create temporary file for the test");
+    std::fs::write(todo_file.path(), content).unwrap();
+
+    let repo = TodoRepository::new(TaskStorage::new(todo_file.path().to_path_buf()));
+
+    (todo_file, repo)
+}
+
+#[test]
+fn it_adds_and_queries_tasks() {
+    let (_file, repo) = repository_with("first +work\n");
+
+    let added = repo.add("second +home", Some('B')).unwrap();
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].id, 2);
+    assert_eq!(added[0].priority, Some('B'));
+
+    let query = "+home".parse::<TaskQuery>().unwrap();
+    let tasks = repo.query(Some(&query), false).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].subject, "second +home");
+}
+
+#[test]
+fn it_completes_and_hides_completed_tasks() {
+    let (_file, repo) = repository_with("first\nsecond\n");
+
+    let completed = repo.complete(&"1".parse().unwrap()).unwrap();
+    assert!(completed[0].completed);
+
+    assert_eq!(repo.query(None, false).unwrap().len(), 1);
+    assert_eq!(repo.query(None, true).unwrap().len(), 2);
+}
+
+#[test]
+fn it_modifies_and_removes_tasks() {
+    let (file, repo) = repository_with("first\nsecond\n");
+    let update = TaskUpdate {
+        priority: Some(Some('A')),
+        due_date: Some(NaiveDate::from_ymd_opt(2024, 6, 1)),
+        ..TaskUpdate::default()
+    };
+
+    let modified = repo.modify(&"2".parse().unwrap(), &update).unwrap();
+    assert_eq!(modified[0].priority, Some('A'));
+
+    let removed = repo.remove(&"1".parse().unwrap()).unwrap();
+    assert_eq!(removed[0].subject, "first");
+
+    assert_eq!(
+        std::fs::read_to_string(file.path()).unwrap(),
+        "(A) second due:2024-06-01\n"
+    );
+}
+
+#[test]
+fn it_links_tasks_by_their_stable_id() {
+    let (file, repo) = repository_with("release id:4\nwrite notes\nship\n");
+    let update = TaskUpdate {
+        parent: Some(Some(1)),
+        dependencies: Some(vec![2]),
+        ..TaskUpdate::default()
+    };
+
+    let modified = repo.modify(&"3".parse().unwrap(), &update).unwrap();
+    assert_eq!(modified[0].parent(), Some("4"));
+    assert_eq!(modified[0].dependencies().collect::<Vec<_>>(), ["5"]);
+
+    // The blocking task was given an id to be referred to
+    let content = std::fs::read_to_string(file.path()).unwrap();
+    assert!(content.lines().nth(1).unwrap().ends_with("id:5"));
+
+    let update = TaskUpdate {
+        parent: Some(None),
+        dependencies: Some(Vec::new()),
+        ..TaskUpdate::default()
+    };
+    let modified = repo.modify(&"3".parse().unwrap(), &update).unwrap();
+    assert_eq!(modified[0].parent(), None);
+    assert_eq!(modified[0].dependencies().count(), 0);
+}
+
+#[test]
+fn it_adds_spent_time_to_identified_tasks() {
+    let (_file, repo) = repository_with("first id:3\nsecond spent:45m\n");
+
+    let task = repo.identify(2).unwrap();
+    assert_eq!(task.stable_id(), Some("4"));
+
+    let task = repo.add_spent("4", TimeDelta::minutes(50)).unwrap();
+    assert_eq!(task.tags["spent"], "1h35m");
+
+    assert!(repo.add_spent("9", TimeDelta::minutes(5)).is_err());
+}